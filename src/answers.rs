@@ -0,0 +1,246 @@
+use crate::solution::Report;
+use std::collections::HashMap;
+
+/// Expected answers, keyed by day and part.
+///
+/// These are stored in a small subset of TOML, one table per day:
+///
+/// ```toml
+/// [day01]
+/// part1 = 1688
+/// part2 = "1728"
+/// ```
+#[derive(Debug, Default, Eq, PartialEq)]
+pub struct Answers {
+    answers: HashMap<(i32, u8), String>,
+}
+
+fn parse_string(s: &str) -> Option<String> {
+    let mut chars = s.strip_prefix('"')?.strip_suffix('"')?.chars();
+    let mut r = String::new();
+    while let Some(ch) = chars.next() {
+        match ch {
+            '\\' => match chars.next()? {
+                'n' => r.push('\n'),
+                'r' => r.push('\r'),
+                't' => r.push('\t'),
+                '"' => r.push('"'),
+                '\\' => r.push('\\'),
+                _ => return None,
+            },
+            '"' => return None,
+            ch => r.push(ch),
+        }
+    }
+    Some(r)
+}
+
+fn parse_value(s: &str) -> Option<String> {
+    if s.starts_with('"') {
+        parse_string(s)
+    } else if s.parse::<i64>().is_ok() {
+        Some(s.to_string())
+    } else {
+        None
+    }
+}
+
+impl Answers {
+    pub fn parse(input: &str) -> Result<Answers, String> {
+        let mut answers = HashMap::new();
+        let mut day = None;
+
+        for (idx, line) in input.lines().enumerate() {
+            let err = |msg: &str| format!("line {}: {}", idx + 1, msg);
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            if let Some(table) = line.strip_prefix('[') {
+                let d = table
+                    .strip_suffix(']')
+                    .and_then(|t| t.strip_prefix("day"))
+                    .and_then(|d| d.parse::<i32>().ok())
+                    .ok_or_else(|| err("expected a table like [day01]"))?;
+                day = Some(d);
+                continue;
+            }
+
+            let day = day.ok_or_else(|| err("expected a [dayNN] table before any answers"))?;
+            let (key, value) = line
+                .split_once('=')
+                .ok_or_else(|| err("expected part1 = ... or part2 = ..."))?;
+            let part = match key.trim() {
+                "part1" => 1,
+                "part2" => 2,
+                _ => return Err(err("expected part1 or part2")),
+            };
+            let value =
+                parse_value(value.trim()).ok_or_else(|| err("expected a string or integer"))?;
+            answers.insert((day, part), value);
+        }
+
+        Ok(Answers { answers })
+    }
+
+    pub fn load(path: &str) -> Result<Answers, String> {
+        let input = std::fs::read_to_string(path)
+            .map_err(|err| format!("Could not read answers from {}: {}", path, err))?;
+        Answers::parse(&input).map_err(|err| format!("{}, {}", path, err))
+    }
+
+    pub fn get(&self, day: i32, part: u8) -> Option<&str> {
+        self.answers.get(&(day, part)).map(|s| s.as_str())
+    }
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum Verdict {
+    Pass,
+    Fail { expected: String, actual: String },
+    Missing { actual: String },
+}
+
+/// Compares each part of a report against the expected answers.
+pub fn verify(answers: &Answers, report: &Report) -> Vec<(u8, Verdict)> {
+    [(1, &report.part1), (2, &report.part2)]
+        .into_iter()
+        .filter_map(|(part, answer)| {
+            let actual = answer.as_ref()?.value.clone();
+            let verdict = match answers.get(report.day, part) {
+                Some(expected) if expected == actual => Verdict::Pass,
+                Some(expected) => Verdict::Fail {
+                    expected: expected.to_string(),
+                    actual,
+                },
+                None => Verdict::Missing { actual },
+            };
+            Some((part, verdict))
+        })
+        .collect()
+}
+
+pub fn format_verdict(day: i32, part: u8, verdict: &Verdict) -> String {
+    // multi-line answers (like day 13's letters) are easier to compare escaped
+    let show = |s: &str| s.escape_debug().to_string();
+    match verdict {
+        Verdict::Pass => format!("Day {},\tpart {}: PASS", day, part),
+        Verdict::Fail { expected, actual } => format!(
+            "Day {},\tpart {}: FAIL (expected {}, got {})",
+            day,
+            part,
+            show(expected),
+            show(actual)
+        ),
+        Verdict::Missing { actual } => format!(
+            "Day {},\tpart {}: MISSING (got {})",
+            day,
+            part,
+            show(actual)
+        ),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::solution::Answer;
+
+    fn ex1() -> String {
+        [
+            "# comment",
+            "[day01]",
+            "part1 = 1688",
+            "part2 = \"1728\"",
+            "",
+            "[day13]",
+            "part2 = \"\\n# \\\"#\\\"\\n\"",
+        ]
+        .join("\n")
+    }
+
+    fn report(day: i32, part1: &str, part2: &str) -> Report {
+        let answer = |value: &str| {
+            Some(Answer {
+                value: value.to_string(),
+                time_ms: 0.,
+            })
+        };
+        Report {
+            day,
+            parse_ms: 0.,
+            part1: answer(part1),
+            part2: answer(part2),
+            total_ms: 0.,
+            cpu_ms: None,
+        }
+    }
+
+    #[test]
+    fn test_parse() {
+        let actual = Answers::parse(&ex1()).unwrap();
+        assert_eq!(actual.get(1, 1), Some("1688"));
+        assert_eq!(actual.get(1, 2), Some("1728"));
+        assert_eq!(actual.get(13, 1), None);
+        assert_eq!(actual.get(13, 2), Some("\n# \"#\"\n"));
+    }
+
+    #[test]
+    fn test_parse_invalid() {
+        assert_eq!(
+            Answers::parse("part1 = 1"),
+            Err("line 1: expected a [dayNN] table before any answers".to_string())
+        );
+        assert_eq!(
+            Answers::parse("[day01]\npart3 = 1"),
+            Err("line 2: expected part1 or part2".to_string())
+        );
+        assert_eq!(
+            Answers::parse("[day01]\npart1 = abc"),
+            Err("line 2: expected a string or integer".to_string())
+        );
+    }
+
+    #[test]
+    fn test_verify() {
+        let answers = Answers::parse(&ex1()).unwrap();
+        let actual = verify(&answers, &report(1, "1688", "1729"));
+        assert_eq!(
+            actual,
+            vec![
+                (1, Verdict::Pass),
+                (
+                    2,
+                    Verdict::Fail {
+                        expected: "1728".to_string(),
+                        actual: "1729".to_string()
+                    }
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_verify_missing() {
+        let answers = Answers::parse(&ex1()).unwrap();
+        let actual = verify(&answers, &report(2, "5", "6"));
+        assert_eq!(
+            actual[0],
+            (
+                1,
+                Verdict::Missing {
+                    actual: "5".to_string()
+                }
+            )
+        );
+        assert!(matches!(actual[1].1, Verdict::Missing { .. }));
+    }
+
+    #[test]
+    fn test_answers_file() {
+        let answers = Answers::load("answers.toml").unwrap();
+        assert_eq!(answers.get(1, 1), Some("1688"));
+        assert_eq!(answers.get(25, 1), Some("557"));
+    }
+}
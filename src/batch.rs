@@ -0,0 +1,261 @@
+use crate::cli::Part;
+use crate::isolate::{self, Failure};
+use crate::solution::{Answer, Report, Runner};
+use crate::util;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+/// Matches a file name against a pattern where `*` is any run of characters and `?` is any one.
+fn glob_match(pattern: &[char], name: &[char]) -> bool {
+    match (pattern.first(), name.first()) {
+        (None, None) => true,
+        (Some('*'), _) => {
+            glob_match(&pattern[1..], name) || (!name.is_empty() && glob_match(pattern, &name[1..]))
+        }
+        (Some('?'), Some(_)) => glob_match(&pattern[1..], &name[1..]),
+        (Some(p), Some(n)) if p == n => glob_match(&pattern[1..], &name[1..]),
+        _ => false,
+    }
+}
+
+fn list_files(dir: &Path, pattern: Option<&str>) -> Result<Vec<PathBuf>, String> {
+    let entries =
+        fs::read_dir(dir).map_err(|err| format!("Could not read {}: {}", dir.display(), err))?;
+    let pattern: Option<Vec<char>> = pattern.map(|p| p.chars().collect());
+
+    let mut r = vec![];
+    for entry in entries.flatten() {
+        let path = entry.path();
+        let name: Vec<char> = entry.file_name().to_string_lossy().chars().collect();
+        let matches = pattern.as_ref().is_none_or(|p| glob_match(p, &name));
+        if matches && path.is_file() {
+            r.push(path);
+        }
+    }
+    r.sort();
+    Ok(r)
+}
+
+/// Expands a directory (every file in it), a glob in the file name (`inputs/day04-*.txt`) or a
+/// single file into a sorted list of inputs.
+pub fn expand(spec: &str) -> Result<Vec<PathBuf>, String> {
+    let path = Path::new(spec);
+    let name = path.file_name().map(|n| n.to_string_lossy());
+    let paths = match name {
+        Some(name) if name.contains(['*', '?']) => {
+            let dir = match path.parent() {
+                Some(dir) if dir != Path::new("") => dir,
+                _ => Path::new("."),
+            };
+            list_files(dir, Some(&name))?
+        }
+        _ if path.is_dir() => list_files(path, None)?,
+        _ => vec![path.to_path_buf()],
+    };
+
+    if paths.is_empty() {
+        return Err(format!("No input files match {}", spec));
+    }
+    Ok(paths)
+}
+
+/// Why an input has no report.
+#[derive(Clone, Debug, PartialEq)]
+pub enum RowError {
+    /// The input couldn't be read or parsed.
+    Error(String),
+    /// The day panicked or timed out on the input.
+    Failed(Failure),
+}
+
+pub struct BatchRow {
+    pub path: String,
+    pub report: Result<Report, RowError>,
+}
+
+/// Runs one day against each of the inputs, in order, isolating each run like the normal runner
+/// does so that one bad input doesn't stop the others.
+pub fn run(
+    runner: &'static dyn Runner,
+    paths: &[PathBuf],
+    part: Part,
+    timeout: Option<Duration>,
+) -> Vec<BatchRow> {
+    paths
+        .iter()
+        .map(|path| {
+            let report = match util::read_input_file(path) {
+                Ok(input) => match isolate::run(runner, input, part, timeout) {
+                    Ok(Ok(report)) => Ok(report),
+                    Ok(Err(err)) => Err(RowError::Error(err.to_string())),
+                    Err(failure) => Err(RowError::Failed(failure)),
+                },
+                Err(err) => Err(RowError::Error(err)),
+            };
+            BatchRow {
+                path: path.display().to_string(),
+                report,
+            }
+        })
+        .collect()
+}
+
+fn cells(row: &BatchRow) -> Vec<String> {
+    // multi-line answers (like day 13's letters) would break the table
+    let answer = |a: &Option<Answer>| {
+        a.as_ref()
+            .map_or("-".to_string(), |a| a.value.escape_debug().to_string())
+    };
+    match &row.report {
+        Ok(report) => vec![
+            row.path.clone(),
+            answer(&report.part1),
+            answer(&report.part2),
+            format!("{:.3}", report.parse_ms),
+            format!("{:.3}", report.part1.as_ref().map_or(0., |a| a.time_ms)),
+            format!("{:.3}", report.part2.as_ref().map_or(0., |a| a.time_ms)),
+            format!("{:.3}", report.total_ms),
+        ],
+        Err(RowError::Error(err)) => vec![row.path.clone(), format!("ERROR: {}", err)],
+        Err(RowError::Failed(failure)) => vec![row.path.clone(), failure.to_string()],
+    }
+}
+
+/// Formats the rows as a table with one line per input file.
+pub fn format_table(rows: &[BatchRow]) -> String {
+    let header: Vec<String> = [
+        "file",
+        "part 1",
+        "part 2",
+        "parse ms",
+        "part 1 ms",
+        "part 2 ms",
+        "total ms",
+    ]
+    .iter()
+    .map(|s| s.to_string())
+    .collect();
+    let mut lines = vec![header];
+    lines.extend(rows.iter().map(cells));
+
+    // error rows have fewer cells, and shouldn't widen the columns
+    let columns = lines[0].len();
+    let mut widths = vec![0; columns];
+    for line in lines.iter().filter(|line| line.len() == columns) {
+        for (width, cell) in widths.iter_mut().zip(line) {
+            *width = (*width).max(cell.chars().count());
+        }
+    }
+
+    let mut r = String::new();
+    for line in lines {
+        let cells: Vec<String> = line
+            .iter()
+            .zip(&widths)
+            .map(|(cell, width)| format!("{:width$}", cell, width = width))
+            .collect();
+        r += cells.join("  ").trim_end();
+        r += "\n";
+    }
+    r
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn matches(pattern: &str, name: &str) -> bool {
+        let p: Vec<char> = pattern.chars().collect();
+        let n: Vec<char> = name.chars().collect();
+        glob_match(&p, &n)
+    }
+
+    #[test]
+    fn test_glob_match() {
+        assert!(matches("day04.txt", "day04.txt"));
+        assert!(matches("day04-*.txt", "day04-alice.txt"));
+        assert!(matches("day04-*.txt", "day04-.txt"));
+        assert!(matches("day0?.txt", "day07.txt"));
+        assert!(matches("*", "anything"));
+        assert!(!matches("day04-*.txt", "day04.txt"));
+        assert!(!matches("day0?.txt", "day10.txt"));
+    }
+
+    #[test]
+    fn test_expand_glob() {
+        let actual = expand("src/day0?.txt").unwrap();
+        assert_eq!(actual.len(), 9);
+        assert_eq!(actual[0], PathBuf::from("src/day01.txt"));
+        assert_eq!(actual[8], PathBuf::from("src/day09.txt"));
+    }
+
+    #[test]
+    fn test_expand_no_match() {
+        assert_eq!(
+            expand("src/day99-*.txt"),
+            Err("No input files match src/day99-*.txt".to_string())
+        );
+    }
+
+    #[test]
+    fn test_run_and_format() {
+        let runner = crate::solution::find(1).unwrap();
+        let paths = vec![
+            PathBuf::from("src/day01.txt"),
+            PathBuf::from("src/day02.txt"),
+        ];
+        let mut rows = run(runner, &paths, Part::Both, None);
+        assert!(rows[1].report.is_err());
+
+        let mut report = rows[0].report.clone().unwrap();
+        assert_eq!(
+            report.part1.as_ref().map(|a| a.value.as_str()),
+            Some("1688")
+        );
+
+        // fixed timings, so that the table is predictable
+        report.parse_ms = 0.25;
+        report.part1.as_mut().unwrap().time_ms = 1.;
+        report.part2.as_mut().unwrap().time_ms = 2.;
+        report.total_ms = 3.25;
+        rows[0].report = Ok(report);
+        rows[1].report = Err(RowError::Error(
+            "day 1, line 1, column 1: expected a number".to_string(),
+        ));
+        assert_eq!(
+            format_table(&rows),
+            [
+                "file           part 1  part 2  parse ms  part 1 ms  part 2 ms  total ms",
+                "src/day01.txt  1688    1728    0.250     1.000      2.000      3.250",
+                "src/day02.txt  ERROR: day 1, line 1, column 1: expected a number",
+                "",
+            ]
+            .join("\n")
+        );
+    }
+
+    #[test]
+    fn test_run_isolated() {
+        // day 13 panics with no folds to make, which mustn't stop the next input
+        let path = std::env::temp_dir().join(format!("aoc-batch-{}.txt", std::process::id()));
+        fs::write(&path, "0,0\n").unwrap();
+        let runner = crate::solution::find(13).unwrap();
+        let rows = run(
+            runner,
+            &[path.clone(), PathBuf::from("src/day13.txt")],
+            Part::One,
+            None,
+        );
+        let _ = fs::remove_file(&path);
+
+        let failure = Failure::Panicked("Ran out of folds".to_string());
+        assert_eq!(
+            rows[0].report.as_ref().err(),
+            Some(&RowError::Failed(failure))
+        );
+        assert!(rows[1].report.is_ok());
+        let table = format_table(&rows);
+        assert!(table.contains("FAILED: Ran out of folds\n"), "{}", table);
+    }
+}
@@ -0,0 +1,259 @@
+use crate::cli::Part;
+use crate::error::ParseError;
+use crate::solution::Runner;
+use std::collections::HashMap;
+
+/// Summary of repeated timings, in milliseconds.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Stats {
+    pub min: f64,
+    pub median: f64,
+    pub mean: f64,
+    pub stddev: f64,
+}
+
+impl Stats {
+    pub fn from_samples(samples: &[f64]) -> Stats {
+        let mut sorted = samples.to_vec();
+        sorted.sort_by(|a, b| a.total_cmp(b));
+
+        let n = sorted.len();
+        let median = if n.is_multiple_of(2) {
+            (sorted[n / 2 - 1] + sorted[n / 2]) / 2.
+        } else {
+            sorted[n / 2]
+        };
+        let mean = sorted.iter().sum::<f64>() / n as f64;
+        let variance = sorted.iter().map(|s| (s - mean).powi(2)).sum::<f64>() / n as f64;
+
+        Stats {
+            min: sorted[0],
+            median,
+            mean,
+            stddev: variance.sqrt(),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum Phase {
+    Parse,
+    Part1,
+    Part2,
+}
+
+impl Phase {
+    fn name(&self) -> &'static str {
+        match self {
+            Phase::Parse => "parse",
+            Phase::Part1 => "part1",
+            Phase::Part2 => "part2",
+        }
+    }
+
+    fn parse(s: &str) -> Option<Phase> {
+        match s {
+            "parse" => Some(Phase::Parse),
+            "part1" => Some(Phase::Part1),
+            "part2" => Some(Phase::Part2),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct BenchResult {
+    pub day: i32,
+    pub stats: Vec<(Phase, Stats)>,
+}
+
+/// Runs a day `warmup` times without measuring, then `iterations` more times collecting timings.
+pub fn bench(
+    runner: &dyn Runner,
+    input: &str,
+    part: Part,
+    warmup: usize,
+    iterations: usize,
+) -> Result<BenchResult, ParseError> {
+    for _ in 0..warmup {
+        runner.run(input, part)?;
+    }
+
+    let mut samples: Vec<(Phase, Vec<f64>)> = vec![];
+    for _ in 0..iterations.max(1) {
+        let report = runner.run(input, part)?;
+        let times = [
+            (Phase::Parse, Some(report.parse_ms)),
+            (Phase::Part1, report.part1.map(|a| a.time_ms)),
+            (Phase::Part2, report.part2.map(|a| a.time_ms)),
+        ];
+        for (phase, time) in times {
+            if let Some(time) = time {
+                match samples.iter_mut().find(|(p, _)| *p == phase) {
+                    Some((_, s)) => s.push(time),
+                    None => samples.push((phase, vec![time])),
+                }
+            }
+        }
+    }
+
+    Ok(BenchResult {
+        day: runner.day(),
+        stats: samples
+            .iter()
+            .map(|(phase, s)| (*phase, Stats::from_samples(s)))
+            .collect(),
+    })
+}
+
+/// Previously saved medians, keyed by day and phase.
+#[derive(Debug, Default, PartialEq)]
+pub struct Baseline {
+    medians: HashMap<(i32, Phase), f64>,
+}
+
+const BASELINE_HEADER: &str = "day,phase,min,median,mean,stddev";
+
+impl Baseline {
+    pub fn parse(input: &str) -> Result<Baseline, String> {
+        let mut medians = HashMap::new();
+        for (idx, line) in input.lines().enumerate() {
+            if idx == 0 && line == BASELINE_HEADER || line.is_empty() {
+                continue;
+            }
+            let err = || format!("line {}: expected {}", idx + 1, BASELINE_HEADER);
+            let fields: Vec<&str> = line.split(',').collect();
+            if fields.len() != 6 {
+                return Err(err());
+            }
+            let day = fields[0].parse().map_err(|_| err())?;
+            let phase = Phase::parse(fields[1]).ok_or_else(err)?;
+            let median = fields[3].parse().map_err(|_| err())?;
+            medians.insert((day, phase), median);
+        }
+        Ok(Baseline { medians })
+    }
+
+    pub fn load(path: &str) -> Result<Baseline, String> {
+        let input = std::fs::read_to_string(path)
+            .map_err(|err| format!("Could not read baseline from {}: {}", path, err))?;
+        Baseline::parse(&input).map_err(|err| format!("{}, {}", path, err))
+    }
+
+    pub fn median(&self, day: i32, phase: Phase) -> Option<f64> {
+        self.medians.get(&(day, phase)).copied()
+    }
+}
+
+pub fn format_baseline(results: &[BenchResult]) -> String {
+    let mut r = format!("{}\n", BASELINE_HEADER);
+    for result in results {
+        for (phase, s) in result.stats.iter() {
+            r += &format!(
+                "{},{},{:.6},{:.6},{:.6},{:.6}\n",
+                result.day,
+                phase.name(),
+                s.min,
+                s.median,
+                s.mean,
+                s.stddev
+            );
+        }
+    }
+    r
+}
+
+pub fn format_result(result: &BenchResult, baseline: Option<&Baseline>) -> String {
+    let mut r = String::new();
+    let mut label = format!("Day {},", result.day);
+    for (phase, s) in result.stats.iter() {
+        r += &format!(
+            "{}\t{}: min {:.3} ms, median {:.3} ms, mean {:.3} ms, stddev {:.3} ms",
+            label,
+            phase.name(),
+            s.min,
+            s.median,
+            s.mean,
+            s.stddev
+        );
+        if let Some(before) = baseline.and_then(|b| b.median(result.day, *phase)) {
+            let change = (s.median - before) / before * 100.;
+            r += &format!(" ({:+.1}% vs {:.3} ms)", change, before);
+        }
+        r += "\n";
+        label = " ".repeat(7);
+    }
+    r += "\n";
+    r
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::util;
+
+    #[test]
+    fn test_stats() {
+        let actual = Stats::from_samples(&[4., 1., 3., 2.]);
+        assert_eq!(actual.min, 1.);
+        assert_eq!(actual.median, 2.5);
+        assert_eq!(actual.mean, 2.5);
+        assert!((actual.stddev - 1.25f64.sqrt()).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_stats_odd() {
+        let actual = Stats::from_samples(&[5., 1., 3.]);
+        assert_eq!(actual.median, 3.);
+        assert_eq!(actual.stddev, (8f64 / 3.).sqrt());
+    }
+
+    #[test]
+    fn test_bench() {
+        let runner = crate::solution::find(1).unwrap();
+        let actual = bench(runner, &util::read_input(1), Part::One, 1, 3).unwrap();
+        assert_eq!(actual.day, 1);
+        let phases: Vec<Phase> = actual.stats.iter().map(|(p, _)| *p).collect();
+        assert_eq!(phases, vec![Phase::Parse, Phase::Part1]);
+    }
+
+    #[test]
+    fn test_baseline_round_trip() {
+        let stats = Stats {
+            min: 1.,
+            median: 2.,
+            mean: 2.5,
+            stddev: 0.5,
+        };
+        let results = vec![BenchResult {
+            day: 6,
+            stats: vec![(Phase::Parse, stats), (Phase::Part2, stats)],
+        }];
+        let baseline = Baseline::parse(&format_baseline(&results)).unwrap();
+        assert_eq!(baseline.median(6, Phase::Parse), Some(2.));
+        assert_eq!(baseline.median(6, Phase::Part2), Some(2.));
+        assert_eq!(baseline.median(6, Phase::Part1), None);
+    }
+
+    #[test]
+    fn test_format_result_with_baseline() {
+        let result = BenchResult {
+            day: 15,
+            stats: vec![(
+                Phase::Part1,
+                Stats {
+                    min: 1.,
+                    median: 1.5,
+                    mean: 1.5,
+                    stddev: 0.25,
+                },
+            )],
+        };
+        let baseline =
+            Baseline::parse("day,phase,min,median,mean,stddev\n15,part1,1,2,2,0").unwrap();
+        assert_eq!(
+            format_result(&result, Some(&baseline)),
+            "Day 15,\tpart1: min 1.000 ms, median 1.500 ms, mean 1.500 ms, stddev 0.250 ms (-25.0% vs 2.000 ms)\n\n"
+        );
+    }
+}
@@ -0,0 +1,461 @@
+use crate::output::Format;
+use crate::parallel;
+use crate::scaffold::{self, Scaffold};
+use std::collections::HashMap;
+use std::time::Duration;
+
+pub const NUM_DAYS: i32 = 25;
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Part {
+    One,
+    Two,
+    Both,
+}
+
+impl Part {
+    fn parse(s: &str) -> Result<Part, String> {
+        match s {
+            "1" => Ok(Part::One),
+            "2" => Ok(Part::Two),
+            "both" => Ok(Part::Both),
+            _ => Err(format!("Invalid part '{}', expected 1, 2 or both", s)),
+        }
+    }
+
+    pub fn includes_part1(&self) -> bool {
+        *self != Part::Two
+    }
+
+    pub fn includes_part2(&self) -> bool {
+        *self != Part::One
+    }
+}
+
+#[derive(Debug, Eq, PartialEq)]
+pub struct Options {
+    pub days: Vec<i32>,
+    pub part: Part,
+    pub inputs: HashMap<i32, String>,
+    pub input_dir: Option<String>,
+    pub batch: Option<String>,
+    pub explain: Option<String>,
+    pub format: Format,
+    pub jobs: usize,
+    pub timeout: Option<Duration>,
+    pub verify: bool,
+    pub answers: String,
+    pub bench: Option<usize>,
+    pub warmup: usize,
+    pub baseline: Option<String>,
+    pub save_baseline: Option<String>,
+    pub help: bool,
+    pub list: bool,
+}
+
+impl Options {
+    pub fn input_for(&self, day: i32) -> Option<&str> {
+        self.inputs.get(&day).map(|s| s.as_str())
+    }
+}
+
+pub const USAGE: &str = "\
+Usage: advent-of-code-2021 [OPTIONS] [DAYS...]
+       advent-of-code-2021 scaffold [OPTIONS] <DAY>
+
+Arguments:
+  [DAYS...]  Days to run: `all`, a single day (`3`), a range (`5-9`),
+             or a comma separated list of those (`1,3,5-9`) [default: all]
+
+Options:
+  -p, --part <PART>         Part to run: 1, 2 or both [default: both]
+  -i, --input <DAY>=<PATH>  Read the input for DAY from PATH, or from stdin if PATH is `-`
+      --batch <PATH>        Run a single day against every input in a directory, or
+                            matching a glob such as `inputs/day04-*.txt`
+      --explain <WHAT>      Show one of a single day's views of how it finds its answers,
+                            such as `15 --explain route`
+      --input-dir <DIR>     Read dayNN.txt inputs from DIR [default: $AOC_INPUT_DIR or ./src]
+  -f, --format <FORMAT>     Output format: text, json (one object per line) or csv
+                            [default: text]
+      --verify              Check the answers against the expected answers file
+      --answers <PATH>      Expected answers for --verify [default: answers.toml]
+      --bench <N>           Time each phase over N runs and report min, median, mean
+                            and standard deviation
+      --warmup <N>          Unmeasured runs before benchmarking [default: 3]
+      --baseline <PATH>     Compare benchmark medians against a saved baseline
+      --save-baseline <PATH>
+                            Save the benchmark results as a baseline
+  -j, --jobs <N>            Solve up to N days at once, 0 for one per CPU [default: 1]
+  -t, --timeout <SECS>      Give up on a day after SECS seconds, reporting it as TIMEOUT
+  -l, --list                List the implemented days
+  -h, --help                Print this help";
+
+pub const SCAFFOLD_USAGE: &str = "\
+Usage: advent-of-code-2021 scaffold [OPTIONS] <DAY>
+
+Downloads the input for DAY to src/dayNN.txt (unless it is already there), creates src/dayNN.rs
+from a template and registers it with the runner.
+
+Options:
+      --title <TITLE>       The puzzle's title [default: TODO]
+      --year <YEAR>         [default: 2021]
+      --base-url <URL>      Where to download from [default: $AOC_BASE_URL or
+                            https://adventofcode.com]
+      --cookies <PATH>      curl cookie file with the session cookie [default: .cookies.txt]
+      --no-fetch            Don't download the input
+  -h, --help                Print this help";
+
+fn parse_day(s: &str) -> Result<i32, String> {
+    let day = s
+        .parse::<i32>()
+        .map_err(|_| format!("Invalid day '{}'", s))?;
+    if !(1..=NUM_DAYS).contains(&day) {
+        return Err(format!("Day {} is out of range 1-{}", day, NUM_DAYS));
+    }
+    Ok(day)
+}
+
+/// Parses a day selection such as `all`, `3`, `5-9` or `1,3,5-9`.
+pub fn parse_days(spec: &str) -> Result<Vec<i32>, String> {
+    let mut days = vec![];
+
+    for part in spec.split(',') {
+        if part == "all" {
+            days.extend(1..=NUM_DAYS);
+        } else if let Some((from, to)) = part.split_once('-') {
+            let from = parse_day(from)?;
+            let to = parse_day(to)?;
+            if from > to {
+                return Err(format!("Invalid day range '{}'", part));
+            }
+            days.extend(from..=to);
+        } else {
+            days.push(parse_day(part)?);
+        }
+    }
+
+    days.sort_unstable();
+    days.dedup();
+    Ok(days)
+}
+
+fn parse_count(flag: &str, s: &str) -> Result<usize, String> {
+    s.parse()
+        .map_err(|_| format!("Invalid value '{}' for {}, expected a number", s, flag))
+}
+
+fn parse_timeout(s: &str) -> Result<Duration, String> {
+    match s.parse::<f64>() {
+        Ok(secs) if secs > 0. && secs.is_finite() => Ok(Duration::from_secs_f64(secs)),
+        _ => Err(format!(
+            "Invalid timeout '{}', expected a positive number of seconds",
+            s
+        )),
+    }
+}
+
+fn parse_input(s: &str) -> Result<(i32, String), String> {
+    let (day, path) = s
+        .split_once('=')
+        .ok_or_else(|| format!("Invalid input '{}', expected <DAY>=<PATH>", s))?;
+    Ok((parse_day(day)?, path.to_string()))
+}
+
+pub fn parse_args<I: Iterator<Item = String>>(mut args: I) -> Result<Options, String> {
+    let mut days = vec![];
+    let mut part = Part::Both;
+    let mut inputs = HashMap::new();
+    let mut input_dir = None;
+    let mut batch = None;
+    let mut explain = None;
+    let mut format = Format::Text;
+    let mut jobs = 1;
+    let mut timeout = None;
+    let mut verify = false;
+    let mut answers = "answers.toml".to_string();
+    let mut bench = None;
+    let mut warmup = 3;
+    let mut baseline = None;
+    let mut save_baseline = None;
+    let mut help = false;
+    let mut list = false;
+
+    while let Some(arg) = args.next() {
+        // accept both `--part 1` and `--part=1`
+        let (flag, inline_value) = match arg.split_once('=') {
+            Some((flag, value)) if flag.starts_with("--") => (flag.to_string(), Some(value)),
+            _ => (arg.clone(), None),
+        };
+        let mut value = || {
+            inline_value
+                .map(|v| v.to_string())
+                .or_else(|| args.next())
+                .ok_or_else(|| format!("Missing value for {}", flag))
+        };
+
+        match flag.as_str() {
+            "-h" | "--help" => help = true,
+            "-l" | "--list" => list = true,
+            "-f" | "--format" => format = Format::parse(&value()?)?,
+            "-j" | "--jobs" => match parse_count(&flag, &value()?)? {
+                0 => jobs = parallel::available_jobs(),
+                n => jobs = n,
+            },
+            "-t" | "--timeout" => timeout = Some(parse_timeout(&value()?)?),
+            "--verify" => verify = true,
+            "--answers" => answers = value()?,
+            "--bench" => match parse_count(&flag, &value()?)? {
+                0 => return Err("--bench needs at least one run".to_string()),
+                n => bench = Some(n),
+            },
+            "--warmup" => warmup = parse_count(&flag, &value()?)?,
+            "--baseline" => baseline = Some(value()?),
+            "--save-baseline" => save_baseline = Some(value()?),
+            "-p" | "--part" => part = Part::parse(&value()?)?,
+            "-i" | "--input" => {
+                let (day, path) = parse_input(&value()?)?;
+                inputs.insert(day, path);
+            }
+            "--input-dir" => input_dir = Some(value()?),
+            "--batch" => batch = Some(value()?),
+            "--explain" => explain = Some(value()?),
+            _ if flag.starts_with('-') => return Err(format!("Unknown option '{}'", flag)),
+            _ => days.extend(parse_days(&arg)?),
+        }
+    }
+
+    if days.is_empty() {
+        days.extend(1..=NUM_DAYS);
+    }
+    days.sort_unstable();
+    days.dedup();
+
+    if batch.is_some() && days.len() != 1 {
+        return Err("--batch needs exactly one day".to_string());
+    }
+    if explain.is_some() && days.len() != 1 {
+        return Err("--explain needs exactly one day".to_string());
+    }
+
+    Ok(Options {
+        days,
+        part,
+        inputs,
+        input_dir,
+        batch,
+        explain,
+        format,
+        jobs,
+        timeout,
+        verify,
+        answers,
+        bench,
+        warmup,
+        baseline,
+        save_baseline,
+        help,
+        list,
+    })
+}
+
+/// Parses the arguments following `scaffold`. Returns `None` if help was asked for.
+pub fn parse_scaffold_args<I: Iterator<Item = String>>(
+    mut args: I,
+) -> Result<Option<Scaffold>, String> {
+    let mut day = None;
+    let mut year = 2021;
+    let mut title = "TODO".to_string();
+    let mut base_url = std::env::var(scaffold::BASE_URL_VAR)
+        .unwrap_or_else(|_| scaffold::DEFAULT_BASE_URL.to_string());
+    let mut cookies = ".cookies.txt".to_string();
+    let mut fetch = true;
+
+    while let Some(arg) = args.next() {
+        let (flag, inline_value) = match arg.split_once('=') {
+            Some((flag, value)) if flag.starts_with("--") => (flag.to_string(), Some(value)),
+            _ => (arg.clone(), None),
+        };
+        let mut value = || {
+            inline_value
+                .map(|v| v.to_string())
+                .or_else(|| args.next())
+                .ok_or_else(|| format!("Missing value for {}", flag))
+        };
+
+        match flag.as_str() {
+            "-h" | "--help" => return Ok(None),
+            "--title" => title = value()?,
+            "--year" => {
+                let v = value()?;
+                year = v.parse().map_err(|_| format!("Invalid year '{}'", v))?;
+            }
+            "--base-url" => base_url = value()?,
+            "--cookies" => cookies = value()?,
+            "--no-fetch" => fetch = false,
+            _ if flag.starts_with('-') => return Err(format!("Unknown option '{}'", flag)),
+            _ if day.is_some() => return Err(format!("Unexpected argument '{}'", arg)),
+            _ => day = Some(parse_day(&arg)?),
+        }
+    }
+
+    Ok(Some(Scaffold {
+        day: day.ok_or("Missing the day to scaffold")?,
+        year,
+        title,
+        base_url,
+        cookies,
+        root: std::path::PathBuf::from("."),
+        fetch,
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(a: &[&str]) -> Result<Options, String> {
+        parse_args(a.iter().map(|s| s.to_string()))
+    }
+
+    #[test]
+    fn test_parse_days() {
+        assert_eq!(parse_days("3"), Ok(vec![3]));
+        assert_eq!(parse_days("5-9"), Ok(vec![5, 6, 7, 8, 9]));
+        assert_eq!(parse_days("9,1,3-4,3"), Ok(vec![1, 3, 4, 9]));
+        assert_eq!(parse_days("all"), Ok((1..=25).collect()));
+    }
+
+    #[test]
+    fn test_parse_days_invalid() {
+        assert!(parse_days("0").is_err());
+        assert!(parse_days("26").is_err());
+        assert!(parse_days("9-5").is_err());
+        assert!(parse_days("x").is_err());
+    }
+
+    #[test]
+    fn test_parse_args_defaults() {
+        let actual = args(&[]).unwrap();
+        assert_eq!(actual.days, (1..=25).collect::<Vec<_>>());
+        assert_eq!(actual.part, Part::Both);
+        assert!(actual.inputs.is_empty());
+        assert_eq!(actual.format, Format::Text);
+        assert!(!actual.verify);
+        assert_eq!(actual.answers, "answers.toml");
+        assert_eq!(actual.bench, None);
+        assert_eq!(actual.warmup, 3);
+    }
+
+    #[test]
+    fn test_parse_args() {
+        let actual = args(&["3", "--part", "2", "-i", "3=/tmp/in.txt", "5-6"]).unwrap();
+        assert_eq!(actual.days, vec![3, 5, 6]);
+        assert_eq!(actual.part, Part::Two);
+        assert_eq!(actual.input_for(3), Some("/tmp/in.txt"));
+        assert_eq!(actual.input_for(5), None);
+    }
+
+    #[test]
+    fn test_parse_args_input_dir() {
+        let actual = args(&["--input-dir", "inputs/bob", "-i", "2=-"]).unwrap();
+        assert_eq!(actual.input_dir, Some("inputs/bob".to_string()));
+        assert_eq!(actual.input_for(2), Some("-"));
+    }
+
+    #[test]
+    fn test_parse_args_inline_value() {
+        let actual = args(&["--part=1", "--input=4=four.txt", "--format=csv"]).unwrap();
+        assert_eq!(actual.part, Part::One);
+        assert_eq!(actual.format, Format::Csv);
+        assert_eq!(actual.input_for(4), Some("four.txt"));
+    }
+
+    #[test]
+    fn test_parse_args_batch() {
+        let actual = args(&["4", "--batch", "inputs/day04-*.txt"]).unwrap();
+        assert_eq!(actual.days, vec![4]);
+        assert_eq!(actual.batch, Some("inputs/day04-*.txt".to_string()));
+        assert!(args(&["--batch", "inputs"]).is_err());
+    }
+
+    #[test]
+    fn test_parse_args_explain() {
+        let actual = args(&["15", "--explain", "route"]).unwrap();
+        assert_eq!(actual.days, vec![15]);
+        assert_eq!(actual.explain, Some("route".to_string()));
+        assert_eq!(args(&[]).unwrap().explain, None);
+        assert!(args(&["--explain=route"]).is_err());
+    }
+
+    #[test]
+    fn test_parse_args_jobs() {
+        assert_eq!(args(&[]).unwrap().jobs, 1);
+        assert_eq!(args(&["-j", "4"]).unwrap().jobs, 4);
+        assert_eq!(
+            args(&["--jobs=0"]).unwrap().jobs,
+            parallel::available_jobs()
+        );
+    }
+
+    #[test]
+    fn test_parse_args_timeout() {
+        assert_eq!(args(&[]).unwrap().timeout, None);
+        assert_eq!(
+            args(&["-t", "2.5"]).unwrap().timeout,
+            Some(Duration::from_millis(2500))
+        );
+        assert!(args(&["--timeout", "0"]).is_err());
+        assert!(args(&["--timeout", "soon"]).is_err());
+    }
+
+    #[test]
+    fn test_parse_args_verify() {
+        let actual = args(&["--verify", "--answers", "mine.toml"]).unwrap();
+        assert!(actual.verify);
+        assert_eq!(actual.answers, "mine.toml");
+    }
+
+    #[test]
+    fn test_parse_args_bench() {
+        let actual = args(&["15", "--bench=20", "--warmup", "1", "--baseline", "b.csv"]).unwrap();
+        assert_eq!(actual.bench, Some(20));
+        assert_eq!(actual.warmup, 1);
+        assert_eq!(actual.baseline, Some("b.csv".to_string()));
+        assert_eq!(actual.save_baseline, None);
+    }
+
+    #[test]
+    fn test_parse_scaffold_args() {
+        let args = [
+            "7",
+            "--title",
+            "The Treachery of Whales",
+            "--base-url=http://localhost:8000",
+        ];
+        let actual = parse_scaffold_args(args.iter().map(|s| s.to_string()))
+            .unwrap()
+            .unwrap();
+        assert_eq!(actual.day, 7);
+        assert_eq!(actual.year, 2021);
+        assert_eq!(actual.title, "The Treachery of Whales");
+        assert_eq!(actual.base_url, "http://localhost:8000");
+        assert!(actual.fetch);
+
+        let scaffold_args = |a: &[&str]| parse_scaffold_args(a.iter().map(|s| s.to_string()));
+        assert_eq!(scaffold_args(&["--help"]), Ok(None));
+        assert!(scaffold_args(&[]).is_err());
+        assert!(scaffold_args(&["7", "8"]).is_err());
+        assert!(scaffold_args(&["26"]).is_err());
+    }
+
+    #[test]
+    fn test_parse_args_invalid() {
+        assert!(args(&["--part", "3"]).is_err());
+        assert!(args(&["--part"]).is_err());
+        assert!(args(&["--input", "3"]).is_err());
+        assert!(args(&["--bogus"]).is_err());
+        assert!(args(&["--format", "xml"]).is_err());
+        assert!(args(&["--bench", "0"]).is_err());
+        assert!(args(&["--warmup", "x"]).is_err());
+    }
+}
@@ -1,5 +1,8 @@
-pub fn parse(input: &str) -> Vec<i32> {
-    crate::util::as_ints(input)
+use crate::error::ParseError;
+use crate::solution::Solution;
+
+pub fn parse(input: &str) -> Result<Vec<i32>, ParseError> {
+    crate::util::as_ints(1, input)
 }
 
 pub fn part1(input: &Vec<i32>) -> i32 {
@@ -16,24 +19,37 @@ pub fn part2(input: &Vec<i32>) -> i32 {
         .sum();
 }
 
+pub struct Day01;
+
+impl Solution for Day01 {
+    type Input = Vec<i32>;
+    type Output1 = i32;
+    type Output2 = i32;
+
+    const DAY: i32 = 1;
+    const TITLE: &'static str = "Sonar Sweep";
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        parse(input)
+    }
+
+    fn part1(input: &Self::Input) -> Self::Output1 {
+        part1(input)
+    }
+
+    fn part2(input: &Self::Input) -> Self::Output2 {
+        part2(input)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     // Note this useful idiom: importing names from outer (for mod tests) scope.
     use super::*;
     use crate::util;
 
-    fn ex1() -> Vec<i32> {
-        return vec![199, 200, 208, 210, 200, 207, 240, 269, 260, 263];
-    }
-
     fn real() -> Vec<i32> {
-        parse(&util::read_input(1))
-    }
-
-    #[test]
-    fn test_part1_ex1() {
-        let actual = part1(&ex1());
-        assert_eq!(actual, 7);
+        parse(&util::read_input(1)).unwrap()
     }
 
     #[test]
@@ -42,12 +58,6 @@ mod tests {
         assert_eq!(actual, 1688);
     }
 
-    #[test]
-    fn test_part2_ex1() {
-        let actual = part2(&ex1());
-        assert_eq!(actual, 5);
-    }
-
     #[test]
     fn test_part2_real() {
         let actual = part2(&real());
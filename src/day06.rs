@@ -1,7 +1,9 @@
+use crate::error::ParseError;
+use crate::solution::Solution;
 use std::collections::HashMap;
 
-pub fn parse(input: &str) -> Vec<i32> {
-    crate::util::as_ints(input)
+pub fn parse(input: &str) -> Result<Vec<i32>, ParseError> {
+    crate::util::as_ints(6, input)
 }
 
 fn next(fish_counts: &HashMap<i32, usize>) -> HashMap<i32, usize> {
@@ -42,23 +44,36 @@ pub fn part2(input: &Vec<i32>) -> usize {
     grow(input, 256)
 }
 
+pub struct Day06;
+
+impl Solution for Day06 {
+    type Input = Vec<i32>;
+    type Output1 = usize;
+    type Output2 = usize;
+
+    const DAY: i32 = 6;
+    const TITLE: &'static str = "Lanternfish";
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        parse(input)
+    }
+
+    fn part1(input: &Self::Input) -> Self::Output1 {
+        part1(input)
+    }
+
+    fn part2(input: &Self::Input) -> Self::Output2 {
+        part2(input)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::util;
 
-    fn ex1() -> Vec<i32> {
-        return vec![3, 4, 3, 1, 2];
-    }
-
     fn real() -> Vec<i32> {
-        parse(&util::read_input(6))
-    }
-
-    #[test]
-    fn test_part1_ex1() {
-        let actual = part1(&ex1());
-        assert_eq!(actual, 5934);
+        parse(&util::read_input(6)).unwrap()
     }
 
     #[test]
@@ -67,12 +82,6 @@ mod tests {
         assert_eq!(actual, 356190);
     }
 
-    #[test]
-    fn test_part2_ex1() {
-        let actual = part2(&ex1());
-        assert_eq!(actual, 26984457539);
-    }
-
     #[test]
     fn test_part2_real() {
         let actual = part2(&real());
@@ -1,12 +1,16 @@
+use crate::error::ParseError;
+use crate::solution::Solution;
+
 pub struct CrabDepths(i32, i32, Vec<i32>);
 
-pub fn parse(input: &str) -> CrabDepths {
-    let input: Vec<i32> = crate::util::as_ints(input);
+pub fn parse(input: &str) -> Result<CrabDepths, ParseError> {
+    let input: Vec<i32> = crate::util::as_ints(7, input)?;
 
-    let min_depth = *input.iter().min().expect("Could not find min");
-    let max_depth = *input.iter().max().expect("Could not find max");
+    let no_crabs = || ParseError::new(7, 1, 1, "at least one crab position");
+    let min_depth = *input.iter().min().ok_or_else(no_crabs)?;
+    let max_depth = *input.iter().max().ok_or_else(no_crabs)?;
 
-    CrabDepths(min_depth, max_depth, input)
+    Ok(CrabDepths(min_depth, max_depth, input))
 }
 
 pub fn part1(CrabDepths(min_depth, max_depth, crab_depths): &CrabDepths) -> i32 {
@@ -37,23 +41,36 @@ pub fn part2(CrabDepths(min_depth, max_depth, crab_depths): &CrabDepths) -> i32
     min_cost
 }
 
+pub struct Day07;
+
+impl Solution for Day07 {
+    type Input = CrabDepths;
+    type Output1 = i32;
+    type Output2 = i32;
+
+    const DAY: i32 = 7;
+    const TITLE: &'static str = "The Treachery of Whales";
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        parse(input)
+    }
+
+    fn part1(input: &Self::Input) -> Self::Output1 {
+        part1(input)
+    }
+
+    fn part2(input: &Self::Input) -> Self::Output2 {
+        part2(input)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::util;
 
-    fn ex1() -> CrabDepths {
-        parse("16,1,2,0,4,2,7,1,2,14")
-    }
-
     fn real() -> CrabDepths {
-        parse(&util::read_input(7))
-    }
-
-    #[test]
-    fn test_part1_ex1() {
-        let actual = part1(&ex1());
-        assert_eq!(actual, 37);
+        parse(&util::read_input(7)).unwrap()
     }
 
     #[test]
@@ -62,12 +79,6 @@ mod tests {
         assert_eq!(actual, 347011);
     }
 
-    #[test]
-    fn test_part2_ex1() {
-        let actual = part2(&ex1());
-        assert_eq!(actual, 168);
-    }
-
     #[test]
     fn test_part2_real() {
         let actual = part2(&real());
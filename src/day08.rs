@@ -1,3 +1,5 @@
+use crate::error::{input_lines, InputLine, ParseError};
+use crate::solution::Solution;
 use std::collections::HashMap;
 
 #[derive(Debug)]
@@ -12,13 +14,26 @@ fn encode(s: &str) -> u8 {
         .fold(0, |a, b| a | (1 << b))
 }
 
-fn parse_pattern(s: &str) -> Pattern {
+fn parse_pattern(line: &InputLine, s: &str) -> Result<Pattern, ParseError> {
+    if !s.chars().all(|ch| ('a'..='g').contains(&ch)) {
+        return Err(line.error_at(s, "segments a through g"));
+    }
     let segments = encode(s);
     let bits = s.len() as u8;
-    Pattern {
+    Ok(Pattern {
         segments,
         num_bits: bits,
-    }
+    })
+}
+
+fn parse_patterns<const N: usize>(line: &InputLine, s: &str) -> Result<[Pattern; N], ParseError> {
+    let patterns = s
+        .split_whitespace()
+        .map(|p| parse_pattern(line, p))
+        .collect::<Result<Vec<Pattern>, ParseError>>()?;
+    patterns
+        .try_into()
+        .map_err(|_| line.error_at(s, &format!("{} patterns", N)))
 }
 
 #[derive(Debug)]
@@ -27,36 +42,17 @@ pub struct Entry {
     output: [Pattern; 4],
 }
 
-fn parse_entry(line: &str) -> Entry {
-    let (patterns, output) = line.split_once(" | ").expect("Could not parse line");
-
-    let mut patterns = patterns.split_whitespace().map(parse_pattern);
-    let patterns = [
-        patterns.next().expect("Could not parse pattern"),
-        patterns.next().expect("Could not parse pattern"),
-        patterns.next().expect("Could not parse pattern"),
-        patterns.next().expect("Could not parse pattern"),
-        patterns.next().expect("Could not parse pattern"),
-        patterns.next().expect("Could not parse pattern"),
-        patterns.next().expect("Could not parse pattern"),
-        patterns.next().expect("Could not parse pattern"),
-        patterns.next().expect("Could not parse pattern"),
-        patterns.next().expect("Could not parse pattern"),
-    ];
-
-    let mut output = output.split_whitespace().map(parse_pattern);
-    let output = [
-        output.next().expect("Could not parse output"),
-        output.next().expect("Could not parse output"),
-        output.next().expect("Could not parse output"),
-        output.next().expect("Could not parse output"),
-    ];
-
-    Entry { patterns, output }
+fn parse_entry(line: InputLine) -> Result<Entry, ParseError> {
+    let (patterns, output) = line.split_once(" | ")?;
+
+    let patterns = parse_patterns(&line, patterns)?;
+    let output = parse_patterns(&line, output)?;
+
+    Ok(Entry { patterns, output })
 }
 
-pub fn parse(input: &str) -> Vec<Entry> {
-    return input.lines().map(parse_entry).collect();
+pub fn parse(input: &str) -> Result<Vec<Entry>, ParseError> {
+    input_lines(8, input).map(parse_entry).collect()
 }
 
 pub fn part1(input: &Vec<Entry>) -> usize {
@@ -142,51 +138,47 @@ pub fn part2(input: &Vec<Entry>) -> i32 {
     input.iter().map(solve).sum()
 }
 
+pub struct Day08;
+
+impl Solution for Day08 {
+    type Input = Vec<Entry>;
+    type Output1 = usize;
+    type Output2 = i32;
+
+    const DAY: i32 = 8;
+    const TITLE: &'static str = "Seven Segment Search";
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        parse(input)
+    }
+
+    fn part1(input: &Self::Input) -> Self::Output1 {
+        part1(input)
+    }
+
+    fn part2(input: &Self::Input) -> Self::Output2 {
+        part2(input)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::util;
 
-    fn ex1() -> String {
-        [
-            "be cfbegad cbdgef fgaecd cgeb fdcge agebfd fecdb fabcd edb | fdgacbe cefdb cefbgd gcbe",
-            "edbfga begcd cbg gc gcadebf fbgde acbgfd abcde gfcbed gfec | fcgedb cgb dgebacf gc",
-            "fgaebd cg bdaec gdafb agbcfd gdcbef bgcad gfac gcb cdgabef | cg cg fdcagb cbg",
-            "fbegcd cbd adcefb dageb afcb bc aefdc ecdab fgdeca fcdbega | efabcd cedba gadfec cb",
-            "aecbfdg fbg gf bafeg dbefa fcge gcbea fcaegb dgceab fcbdga | gecf egdcabf bgf bfgea",
-            "fgeab ca afcebg bdacfeg cfaedg gcfdb baec bfadeg bafgc acf | gebdcfa ecba ca fadegcb",
-            "dbcfg fgd bdegcaf fgec aegbdf ecdfab fbedc dacgb gdcebf gf | cefg dcbef fcge gbcadfe",
-            "bdfegc cbegaf gecbf dfcage bdacg ed bedf ced adcbefg gebcd | ed bcgafe cdgba cbgef",
-            "egadfb cdbfeg cegd fecab cgb gbdefca cg fgcdab egfdb bfceg | gbdfcae bgc cg cgb",
-            "gcafb gcf dcaebfg ecagb gf abcdeg gaef cafbge fdbac fegbdc | fgae cfgab fg bagce",
-        ].join("\n")
-    }
-
     fn real() -> String {
         util::read_input(8)
     }
 
-    #[test]
-    fn test_part1_ex1() {
-        let actual = part1(&parse(&ex1()));
-        assert_eq!(actual, 26);
-    }
-
     #[test]
     fn test_part1_real() {
-        let actual = part1(&parse(&real()));
+        let actual = part1(&parse(&real()).unwrap());
         assert_eq!(actual, 495);
     }
 
-    #[test]
-    fn test_part2_ex1() {
-        let actual = part2(&parse(&ex1()));
-        assert_eq!(actual, 61229);
-    }
-
     #[test]
     fn test_part2_real() {
-        let actual = part2(&parse(&real()));
+        let actual = part2(&parse(&real()).unwrap());
         assert_eq!(actual, 1055164);
     }
 }
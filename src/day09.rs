@@ -1,133 +1,72 @@
+use crate::error::ParseError;
+use crate::solution::Solution;
+use crate::util::search;
+use crate::util::{Edges, Grid, Neighborhood};
+
 pub struct OceanFloor {
-    heights: Vec<Vec<i32>>,
-    max_x: usize,
-    max_y: usize,
+    heights: Grid<i32>,
 }
 
-pub fn parse(input: &str) -> OceanFloor {
-    let mut heights = vec![];
-    let mut y: usize = 0;
-    let mut max_x: usize = 0;
-    for line in input.lines() {
-        let mut x = 0;
-        let mut row = vec![0; line.len()];
-        for ch in line.chars() {
-            let height = ch as i32 - '0' as i32;
-            row[x] = height;
-            x += 1;
-        }
-        heights.push(row);
-        max_x = x;
-        y += 1;
-    }
-    OceanFloor {
-        heights,
-        max_y: y,
-        max_x,
-    }
+pub fn parse(input: &str) -> Result<OceanFloor, ParseError> {
+    let heights = Grid::parse(9, input, "a height digit", |ch| {
+        ch.to_digit(10).map(|height| height as i32)
+    })?;
+    Ok(OceanFloor { heights })
 }
 
-pub fn part1(floor: &OceanFloor) -> i32 {
-    let get = |x: i32, y: i32| {
-        if x < 0 || y < 0 || x >= floor.max_x as i32 || y >= floor.max_y as i32 {
-            return &i32::MAX;
-        }
-        &floor.heights[y as usize][x as usize]
-    };
-
-    let mut risk = 0;
-    for x in 0..floor.max_x {
-        for y in 0..floor.max_y {
-            let x = x as i32;
-            let y = y as i32;
-
-            let n = get(x, y);
-            let north = get(x, y - 1);
-            let south = get(x, y + 1);
-            let west = get(x - 1, y);
-            let east = get(x + 1, y);
-
-            if north > n && south > n && east > n && west > n {
-                risk += n + 1;
-            }
-        }
-    }
-    risk
+/// The positions lower than all of their neighbors.
+fn low_points(heights: &Grid<i32>) -> impl Iterator<Item = (usize, usize)> + '_ {
+    heights.cells().filter_map(move |(pos, n)| {
+        heights
+            .neighbors(pos, Neighborhood::Orthogonal, Edges::Clip)
+            .all(|neighbor| heights[neighbor] > *n)
+            .then_some(pos)
+    })
 }
 
-fn merge_basins(
-    floor: &OceanFloor,
-    basins: &mut Vec<Vec<usize>>,
-    old_basin: usize,
-    new_basin: usize,
-    x: usize,
-    y: usize,
-) {
-    if basins[y][x] == old_basin {
-        basins[y][x] = new_basin;
-        if x > 0 {
-            merge_basins(floor, basins, old_basin, new_basin, x - 1, y);
-        }
-        if x < floor.max_x - 1 {
-            merge_basins(floor, basins, old_basin, new_basin, x + 1, y);
-        }
-        if y > 0 {
-            merge_basins(floor, basins, old_basin, new_basin, x, y - 1);
-        }
-        if y < floor.max_y - 1 {
-            merge_basins(floor, basins, old_basin, new_basin, x, y + 1);
-        }
-    }
+pub fn part1(floor: &OceanFloor) -> i32 {
+    let heights = &floor.heights;
+    low_points(heights).map(|pos| heights[pos] + 1).sum()
 }
+
 pub fn part2(floor: &OceanFloor) -> i32 {
-    let mut basins = vec![vec![0; floor.max_x]; floor.max_y];
-    let mut next_basin: usize = 1;
+    let heights = &floor.heights;
+    // every basin flows down to a single low point, and is walled in by 9s
+    let mut basin_sizes: Vec<usize> = low_points(heights)
+        .map(|low_point| {
+            let successors = |&pos: &(usize, usize)| {
+                heights
+                    .neighbors(pos, Neighborhood::Orthogonal, Edges::Clip)
+                    .filter(|&neighbor| heights[neighbor] != 9)
+            };
+            search::bfs_all(low_point, successors).len()
+        })
+        .collect();
+    basin_sizes.sort_unstable_by(|a, b| b.cmp(a));
+    basin_sizes.iter().take(3).product::<usize>() as i32
+}
+
+pub struct Day09;
 
-    for y in 0..floor.max_y {
-        for x in 0..floor.max_x {
-            let height = floor.heights[y][x];
-            if height == 9 {
-                basins[y][x] = 0;
-                continue;
-            }
+impl Solution for Day09 {
+    type Input = OceanFloor;
+    type Output1 = i32;
+    type Output2 = i32;
 
-            let west_basin = if x == 0 { 0 } else { basins[y][x - 1] };
-            let north_basin = if y == 0 { 0 } else { basins[y - 1][x] };
+    const DAY: i32 = 9;
+    const TITLE: &'static str = "Smoke Basin";
 
-            if west_basin == 0 && north_basin == 0 {
-                basins[y][x] = next_basin;
-                next_basin += 1;
-            } else if west_basin == north_basin {
-                basins[y][x] = west_basin;
-            } else if west_basin == 0 {
-                basins[y][x] = north_basin;
-            } else if north_basin == 0 {
-                basins[y][x] = west_basin;
-            } else {
-                // join two basins
-                basins[y][x] = north_basin;
-                merge_basins(floor, &mut basins, west_basin, north_basin, x - 1, y);
-            }
-        }
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        parse(input)
     }
 
-    let mut basin_sizes = vec![0; next_basin];
+    fn part1(input: &Self::Input) -> Self::Output1 {
+        part1(input)
+    }
 
-    for y in 0..floor.max_y {
-        for x in 0..floor.max_x {
-            // print!("{}", basins[y][x]);
-            let basin = basins[y][x];
-            basin_sizes[basin] += 1;
-        }
-        // println!();
+    fn part2(input: &Self::Input) -> Self::Output2 {
+        part2(input)
     }
-    basin_sizes[0] = 0;
-    basin_sizes.sort_unstable();
-    let n = basin_sizes.len();
-    // for f in &basin_sizes {
-    //     println!("{}", f);
-    // }
-    basin_sizes[n - 1] * basin_sizes[n - 2] * basin_sizes[n - 3]
 }
 
 #[cfg(test)]
@@ -135,42 +74,19 @@ mod tests {
     use super::*;
     use crate::util;
 
-    fn ex1() -> String {
-        vec![
-            "2199943210",
-            "3987894921",
-            "9856789892",
-            "8767896789",
-            "9899965678",
-        ]
-        .join("\n")
-    }
-
     fn real() -> String {
         util::read_input(9)
     }
 
-    #[test]
-    fn test_part1_ex1() {
-        let actual = part1(&parse(&ex1()));
-        assert_eq!(actual, 15);
-    }
-
     #[test]
     fn test_part1_real() {
-        let actual = part1(&parse(&real()));
+        let actual = part1(&parse(&real()).unwrap());
         assert_eq!(actual, 423);
     }
 
-    #[test]
-    fn test_part2_ex1() {
-        let actual = part2(&parse(&ex1()));
-        assert_eq!(actual, 1134);
-    }
-
     #[test]
     fn test_part2_real() {
-        let actual = part2(&parse(&real()));
+        let actual = part2(&parse(&real()).unwrap());
         assert_eq!(actual, 1198704);
     }
 }
@@ -1,5 +1,15 @@
-pub fn parse(input: &str) -> Vec<String> {
-    input.lines().map(str::to_string).collect()
+use crate::error::{input_lines, ParseError};
+use crate::solution::Solution;
+
+pub fn parse(input: &str) -> Result<Vec<String>, ParseError> {
+    input_lines(10, input)
+        .map(
+            |line| match line.chars().find(|(_, ch)| !"()[]{}<>".contains(*ch)) {
+                Some((column, _)) => Err(ParseError::new(10, line.number, column, "a bracket")),
+                None => Ok(line.text.to_string()),
+            },
+        )
+        .collect()
 }
 
 pub fn part1(lines: &Vec<String>) -> i64 {
@@ -73,52 +83,47 @@ pub fn part2(lines: &Vec<String>) -> i64 {
     scores[scores.len() / 2]
 }
 
+pub struct Day10;
+
+impl Solution for Day10 {
+    type Input = Vec<String>;
+    type Output1 = i64;
+    type Output2 = i64;
+
+    const DAY: i32 = 10;
+    const TITLE: &'static str = "Syntax Scoring";
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        parse(input)
+    }
+
+    fn part1(input: &Self::Input) -> Self::Output1 {
+        part1(input)
+    }
+
+    fn part2(input: &Self::Input) -> Self::Output2 {
+        part2(input)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::util;
 
-    fn ex1() -> String {
-        vec![
-            "[({(<(())[]>[[{[]{<()<>>",
-            "[(()[<>])]({[<{<<[]>>(",
-            "{([(<{}[<>[]}>{[]{[(<()>",
-            "(((({<>}<{<{<>}{[]{[]{}",
-            "[[<[([]))<([[{}[[()]]]",
-            "[{[{({}]{}}([{[{{{}}([]",
-            "{<[[]]>}<{[{[{[]{()[[[]",
-            "[<(<(<(<{}))><([]([]()",
-            "<{([([[(<>()){}]>(<<{{",
-            "<{([{{}}[<[[[<>{}]]]>[]]",
-        ]
-        .join("\n")
-    }
-
     fn real() -> String {
         util::read_input(10)
     }
 
-    #[test]
-    fn test_part1_ex1() {
-        let actual = part1(&parse(&ex1()));
-        assert_eq!(actual, 26397);
-    }
-
     #[test]
     fn test_part1_real() {
-        let actual = part1(&parse(&real()));
+        let actual = part1(&parse(&real()).unwrap());
         assert_eq!(actual, 366027);
     }
 
-    #[test]
-    fn test_part2_ex1() {
-        let actual = part2(&parse(&ex1()));
-        assert_eq!(actual, 288957);
-    }
-
     #[test]
     fn test_part2_real() {
-        let actual = part2(&parse(&real()));
+        let actual = part2(&parse(&real()).unwrap());
         assert_eq!(actual, 1118645287);
     }
 }
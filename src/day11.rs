@@ -1,72 +1,56 @@
+use crate::error::ParseError;
+use crate::solution::Solution;
+use crate::util::{neighborhood, Edges, Grid, Neighborhood};
+
 // use std::fmt;
 
 #[derive(Eq, PartialEq, Debug, Clone)]
 pub struct Octopuses {
-    energy_level: Vec<Vec<u8>>,
-    flashed: Vec<Vec<bool>>,
+    energy_level: Grid<u8>,
+    flashed: Grid<bool>,
 }
 
 impl Octopuses {
-    fn parse(input: &str) -> Octopuses {
-        let mut r = Octopuses {
-            energy_level: vec![],
-            flashed: vec![],
-        };
-
-        for line in input.lines() {
-            let mut row = vec![];
-            let mut flashed_row = vec![];
-            for ch in line.chars() {
-                let v = ch.to_digit(10).expect("Could not part digit") as u8;
-                row.push(v);
-                flashed_row.push(v == 0);
-            }
-            r.energy_level.push(row);
-            r.flashed.push(flashed_row);
-        }
-        r
+    fn parse(input: &str) -> Result<Octopuses, ParseError> {
+        let energy_level = Grid::parse(11, input, "an energy level", |ch| {
+            ch.to_digit(10).map(|v| v as u8)
+        })?;
+        let flashed = energy_level.map(|&v| v == 0);
+        Ok(Octopuses {
+            energy_level,
+            flashed,
+        })
     }
 
     fn next(&self) -> Octopuses {
         let mut r = self.clone();
         let mut just_flashed = vec![];
 
-        let max_y = r.energy_level.len();
-        let max_x = r.energy_level[0].len();
+        let size = r.energy_level.size();
 
-        for (y, row) in self.energy_level.iter().enumerate() {
-            for (x, v) in row.iter().enumerate() {
-                r.energy_level[y][x] = v + 1;
-                if *v == 9u8 {
-                    r.flashed[y][x] = true;
-                    just_flashed.push((x, y));
-                } else {
-                    r.flashed[y][x] = false;
-                }
+        for ((x, y), v) in self.energy_level.cells() {
+            r.energy_level[(x, y)] = v + 1;
+            if *v == 9u8 {
+                r.flashed[(x, y)] = true;
+                just_flashed.push((x, y));
+            } else {
+                r.flashed[(x, y)] = false;
             }
         }
 
-        while !just_flashed.is_empty() {
-            let (x, y) = just_flashed.pop().expect("It said it wasn't empty");
-
-            crate::util::neighbors(x, y, max_x, max_y)
-                .iter()
-                .for_each(|(x, y)| {
-                    let x = *x;
-                    let y = *y;
-                    r.energy_level[y][x] += 1;
-                    if r.energy_level[y][x] == 10 {
-                        r.flashed[y][x] = true;
-                        just_flashed.push((x, y));
-                    }
-                });
+        while let Some((x, y)) = just_flashed.pop() {
+            neighborhood((x, y), size, Neighborhood::All, Edges::Clip).for_each(|(x, y)| {
+                r.energy_level[(x, y)] += 1;
+                if r.energy_level[(x, y)] == 10 {
+                    r.flashed[(x, y)] = true;
+                    just_flashed.push((x, y));
+                }
+            });
         }
 
-        for y in 0..max_y {
-            for x in 0..max_x {
-                if r.flashed[y][x] {
-                    r.energy_level[y][x] = 0;
-                }
+        for (level, &flashed) in r.energy_level.iter_mut().zip(r.flashed.iter()) {
+            if flashed {
+                *level = 0;
             }
         }
 
@@ -88,7 +72,7 @@ impl Octopuses {
     // }
 }
 
-pub fn parse(input: &str) -> Octopuses {
+pub fn parse(input: &str) -> Result<Octopuses, ParseError> {
     Octopuses::parse(input)
 }
 
@@ -97,7 +81,7 @@ pub fn _part1(o: &Octopuses, num_rounds: i32) -> usize {
     let mut octopuses = o.clone();
     for _ in 0..num_rounds {
         octopuses = octopuses.next();
-        num_flashes += octopuses.flashed.iter().flatten().filter(|e| **e).count();
+        num_flashes += octopuses.flashed.iter().filter(|e| **e).count();
 
         // println!("After step {} ({})", step + 1, num_flashes);
         // octopuses.energy_level.iter().for_each(|row| {
@@ -119,32 +103,43 @@ pub fn part2(o: &Octopuses) -> i32 {
     let mut octopuses = o.clone();
     let mut round = 0;
 
-    while !octopuses.flashed.iter().flatten().all(|f| *f) {
+    while !octopuses.flashed.iter().all(|f| *f) {
         octopuses = octopuses.next();
         round += 1;
     }
     round
 }
 
+pub struct Day11;
+
+impl Solution for Day11 {
+    type Input = Octopuses;
+    type Output1 = usize;
+    type Output2 = i32;
+
+    const DAY: i32 = 11;
+    const TITLE: &'static str = "Dumbo Octopus";
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        parse(input)
+    }
+
+    fn part1(input: &Self::Input) -> Self::Output1 {
+        part1(input)
+    }
+
+    fn part2(input: &Self::Input) -> Self::Output2 {
+        part2(input)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::util;
 
     fn ex1() -> String {
-        [
-            "5483143223",
-            "2745854711",
-            "5264556173",
-            "6141336146",
-            "6357385478",
-            "4167524645",
-            "2176841721",
-            "6882881134",
-            "4846848554",
-            "5283751526",
-        ]
-        .join("\n")
+        util::read_example(11, 1)
     }
 
     fn real() -> String {
@@ -153,7 +148,7 @@ mod tests {
 
     #[test]
     fn test_parse() {
-        let actual = parse(&ex1());
+        let actual = parse(&ex1()).unwrap();
         assert_eq!(
             actual,
             Octopuses {
@@ -172,46 +167,34 @@ mod tests {
                 .iter()
                 .map(|a| a.to_vec())
                 .collect(),
-                flashed: vec![vec![false; 10]; 10],
+                flashed: vec![vec![false; 10]; 10].into(),
             },
         )
     }
 
     #[test]
     fn test_next() {
-        let before = parse(&["11111", "19991", "19191", "19991", "11111"].join("\n"));
-        let expected = parse(&["34543", "40004", "50005", "40004", "34543"].join("\n"));
+        let before = parse(&["11111", "19991", "19191", "19991", "11111"].join("\n")).unwrap();
+        let expected = parse(&["34543", "40004", "50005", "40004", "34543"].join("\n")).unwrap();
         let actual = before.next();
         assert_eq!(actual, expected);
     }
 
     #[test]
     fn test_part1_ex1_small() {
-        let actual = _part1(&parse(&ex1()), 10);
+        let actual = _part1(&parse(&ex1()).unwrap(), 10);
         assert_eq!(actual, 204);
     }
 
-    #[test]
-    fn test_part1_ex1() {
-        let actual = part1(&parse(&ex1()));
-        assert_eq!(actual, 1656);
-    }
-
     #[test]
     fn test_part1_real() {
-        let actual = part1(&parse(&real()));
+        let actual = part1(&parse(&real()).unwrap());
         assert_eq!(actual, 1661);
     }
 
-    #[test]
-    fn test_part2_ex1() {
-        let actual = part2(&parse(&ex1()));
-        assert_eq!(actual, 195);
-    }
-
     #[test]
     fn test_part2_real() {
-        let actual = part2(&parse(&real()));
+        let actual = part2(&parse(&real()).unwrap());
         assert_eq!(actual, 334);
     }
 }
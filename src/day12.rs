@@ -1,3 +1,5 @@
+use crate::error::{input_lines, ParseError};
+use crate::solution::Solution;
 use std::collections::{HashMap, HashSet};
 
 // TODO: I'm in the middle of trying to repace strings with i32
@@ -70,11 +72,11 @@ impl Caves {
     }
 }
 
-pub fn parse(input: &str) -> Caves {
+pub fn parse(input: &str) -> Result<Caves, ParseError> {
     let mut caves = Caves::init();
 
-    input.lines().for_each(|line| {
-        let (lhs_str, rhs_str) = line.split_once("-").expect("Could not find -");
+    for line in input_lines(12, input) {
+        let (lhs_str, rhs_str) = line.split_once("-")?;
         let lhs_id = caves.upsert(lhs_str).id;
         let rhs_id = caves.upsert(rhs_str).id;
         {
@@ -102,9 +104,9 @@ pub fn parse(input: &str) -> Caves {
         //     let rhs = caves.upsert_mut(rhs_id);
         //     rhs.neighbors.insert(lhs.id);
         // }
-    });
+    }
 
-    caves
+    Ok(caves)
 }
 
 struct Path {
@@ -194,6 +196,29 @@ pub fn part2(caves: &Caves) -> usize {
     num_paths
 }
 
+pub struct Day12;
+
+impl Solution for Day12 {
+    type Input = Caves;
+    type Output1 = usize;
+    type Output2 = usize;
+
+    const DAY: i32 = 12;
+    const TITLE: &'static str = "Passage Pathing";
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        parse(input)
+    }
+
+    fn part1(input: &Self::Input) -> Self::Output1 {
+        part1(input)
+    }
+
+    fn part2(input: &Self::Input) -> Self::Output2 {
+        part2(input)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -206,31 +231,13 @@ mod tests {
     //     }
     // }
 
-    fn ex1() -> String {
-        vec!["start-A", "start-b", "A-c", "A-b", "b-d", "A-end", "b-end"].join("\n")
-    }
-    fn ex2() -> String {
-        vec![
-            "dc-end", "HN-start", "start-kj", "dc-start", "dc-HN", "LN-dc", "HN-end", "kj-sa",
-            "kj-HN", "kj-dc",
-        ]
-        .join("\n")
-    }
-    fn ex3() -> String {
-        vec![
-            "fs-end", "he-DX", "fs-he", "start-DX", "pj-DX", "end-zg", "zg-sl", "zg-pj", "pj-he",
-            "RW-he", "fs-DX", "pj-RW", "zg-RW", "start-pj", "he-WI", "zg-he", "pj-fs", "start-RW",
-        ]
-        .join("\n")
-    }
-
     fn real() -> String {
         util::read_input(12)
     }
 
     // #[test]
     // fn test_parse_ex1() {
-    //     let actual = parse(&ex1());
+    //     let actual = parse(&ex1()).unwrap();
     //     let expected = Caves {
     //         map: [
     //             ("start".to_string(), test_cave("start", &["A", "b"])),
@@ -259,37 +266,16 @@ mod tests {
     //         );
     //     })
     // }
-    #[test]
-    fn test_part1_ex1() {
-        let actual = part1(&parse(&ex1()));
-        assert_eq!(actual, 10);
-    }
-    #[test]
-    fn test_part1_ex2() {
-        let actual = part1(&parse(&ex2()));
-        assert_eq!(actual, 19);
-    }
-    #[test]
-    fn test_part1_ex3() {
-        let actual = part1(&parse(&ex3()));
-        assert_eq!(actual, 226);
-    }
 
     #[test]
     fn test_part1_real() {
-        let actual = part1(&parse(&real()));
+        let actual = part1(&parse(&real()).unwrap());
         assert_eq!(actual, 4970);
     }
 
-    #[test]
-    fn test_part2_ex1() {
-        let actual = part2(&parse(&ex1()));
-        assert_eq!(actual, 36);
-    }
-
     #[test]
     fn test_part2_real() {
-        let actual = part2(&parse(&real()));
+        let actual = part2(&parse(&real()).unwrap());
         assert_eq!(actual, 137948);
     }
 }
@@ -1,3 +1,5 @@
+use crate::error::{input_lines, ParseError};
+use crate::solution::Solution;
 use std::collections::HashSet;
 
 #[derive(Debug, Eq, PartialEq, Clone)]
@@ -39,39 +41,36 @@ impl TransparentPaper {
     }
 }
 
-fn pi32(input: &str) -> i32 {
-    input.parse().expect("Could not parse i32")
-}
-
-pub fn parse(input: &str) -> TransparentPaper {
-    let dots = input
-        .lines()
-        .take_while(|s| !s.is_empty())
+pub fn parse(input: &str) -> Result<TransparentPaper, ParseError> {
+    let dots = input_lines(13, input)
+        .take_while(|line| !line.text.is_empty())
         .map(|line| {
-            let (x, y) = line.split_once(",").expect("Could not parse dot");
-            (pi32(x), pi32(y))
+            let (x, y) = line.split_once(",")?;
+            Ok((
+                line.parse(x, "an x coordinate")?,
+                line.parse(y, "a y coordinate")?,
+            ))
         })
-        .collect();
+        .collect::<Result<_, ParseError>>()?;
 
-    let mut folds: Vec<(FoldDir, i32)> = input
-        .lines()
-        .skip_while(|s| !s.is_empty())
+    let mut folds: Vec<(FoldDir, i32)> = input_lines(13, input)
+        .skip_while(|line| !line.text.is_empty())
         .skip(1)
         .map(|line| {
-            let (dir, val) = line.split_once("=").expect("Could not parse fold");
-            let dir = if dir == "fold along x" {
-                FoldDir::X
-            } else {
-                FoldDir::Y
+            let (dir, val) = line.split_once("=")?;
+            let dir = match dir {
+                "fold along x" => FoldDir::X,
+                "fold along y" => FoldDir::Y,
+                _ => return Err(line.error("fold along x or fold along y")),
             };
-            let val = pi32(val);
-            (dir, val)
+            let val = line.parse(val, "a fold line")?;
+            Ok((dir, val))
         })
-        .collect();
+        .collect::<Result<_, ParseError>>()?;
 
     folds.reverse();
 
-    TransparentPaper { dots, folds }
+    Ok(TransparentPaper { dots, folds })
 }
 
 pub fn part1(paper: &TransparentPaper) -> usize {
@@ -123,36 +122,36 @@ pub fn part2(paper: &TransparentPaper) -> String {
     r
 }
 
+pub struct Day13;
+
+impl Solution for Day13 {
+    type Input = TransparentPaper;
+    type Output1 = usize;
+    type Output2 = String;
+
+    const DAY: i32 = 13;
+    const TITLE: &'static str = "Transparent Origami";
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        parse(input)
+    }
+
+    fn part1(input: &Self::Input) -> Self::Output1 {
+        part1(input)
+    }
+
+    fn part2(input: &Self::Input) -> Self::Output2 {
+        part2(input)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::util;
 
     fn ex1() -> String {
-        vec![
-            "6,10",
-            "0,14",
-            "9,10",
-            "0,3",
-            "10,4",
-            "4,11",
-            "6,0",
-            "6,12",
-            "4,1",
-            "0,13",
-            "10,12",
-            "3,4",
-            "3,0",
-            "8,4",
-            "1,10",
-            "2,14",
-            "8,10",
-            "9,0",
-            "",
-            "fold along y=7",
-            "fold along x=5",
-        ]
-        .join("\n")
+        util::read_example(13, 1)
     }
 
     fn real() -> String {
@@ -161,7 +160,7 @@ mod tests {
 
     #[test]
     fn test_parse_ex1() {
-        let actual = parse(&ex1());
+        let actual = parse(&ex1()).unwrap();
         let expected = TransparentPaper {
             dots: HashSet::from_iter(
                 [
@@ -192,30 +191,15 @@ mod tests {
         assert_eq!(actual, expected);
     }
 
-    #[test]
-    fn test_part1_ex1() {
-        let actual = part1(&parse(&ex1()));
-        assert_eq!(actual, 17);
-    }
-
     #[test]
     fn test_part1_real() {
-        let actual = part1(&parse(&real()));
+        let actual = part1(&parse(&real()).unwrap());
         assert_eq!(actual, 942);
     }
 
-    #[test]
-    fn test_part2_ex1() {
-        let actual = part2(&parse(&ex1()));
-        assert_eq!(
-            actual,
-            vec!["", "#####", "#   #", "#   #", "#   #", "#####", ""].join("\n")
-        );
-    }
-
     #[test]
     fn test_part2_real() {
-        let actual = part2(&parse(&real()));
+        let actual = part2(&parse(&real()).unwrap());
         assert_eq!(
             actual,
             vec![
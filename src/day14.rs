@@ -1,3 +1,5 @@
+use crate::error::{input_lines, ParseError};
+use crate::solution::Solution;
 use std::collections::HashMap;
 
 #[derive(Debug, Eq, PartialEq, Clone)]
@@ -74,26 +76,29 @@ impl Polymerization {
     }
 }
 
-pub fn parse(input: &str) -> Polymerization {
-    let template = input.lines().next().expect("Could not find template");
-    let rules = input
+pub fn parse(input: &str) -> Result<Polymerization, ParseError> {
+    let template = input
         .lines()
+        .next()
+        .filter(|line| !line.is_empty())
+        .ok_or_else(|| ParseError::new(14, 1, 1, "a polymer template"))?;
+    let rules = input_lines(14, input)
         .skip(2)
         .map(|line| {
-            let (ab, c) = line.split_once(" -> ").expect("Could not split rule");
-            let mut ab = ab.chars();
-            let a = ab.next().expect("Could not find a");
-            let b = ab.next().expect("Could not find b");
-            let c = c.chars().next().expect("Could not find c");
+            let (ab, c) = line.split_once(" -> ")?;
+            let (a, b, c) = match (ab.as_bytes(), c.as_bytes()) {
+                (&[a, b], &[c]) => (a as char, b as char, c as char),
+                _ => return Err(line.error("a pair insertion rule like CH -> B")),
+            };
 
-            ((a, b), c)
+            Ok(((a, b), c))
         })
-        .collect();
+        .collect::<Result<_, ParseError>>()?;
 
-    Polymerization {
+    Ok(Polymerization {
         polymer_template: template.to_string(),
         pair_insertion_rules: rules,
-    }
+    })
 }
 
 pub fn part1(poly: &Polymerization) -> usize {
@@ -104,18 +109,36 @@ pub fn part2(poly: &Polymerization) -> usize {
     poly.solve(40)
 }
 
+pub struct Day14;
+
+impl Solution for Day14 {
+    type Input = Polymerization;
+    type Output1 = usize;
+    type Output2 = usize;
+
+    const DAY: i32 = 14;
+    const TITLE: &'static str = "Extended Polymerization";
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        parse(input)
+    }
+
+    fn part1(input: &Self::Input) -> Self::Output1 {
+        part1(input)
+    }
+
+    fn part2(input: &Self::Input) -> Self::Output2 {
+        part2(input)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::util;
 
     fn ex1() -> String {
-        vec![
-            "NNCB", "", "CH -> B", "HH -> N", "CB -> H", "NH -> C", "HB -> C", "HC -> B",
-            "HN -> C", "NN -> C", "BH -> H", "NC -> B", "NB -> B", "BN -> B", "BB -> N", "BC -> B",
-            "CC -> N", "CN -> C",
-        ]
-        .join("\n")
+        util::read_example(14, 1)
     }
 
     fn real() -> String {
@@ -124,7 +147,7 @@ mod tests {
 
     #[test]
     fn test_parse_ex1() {
-        let actual = parse(&ex1());
+        let actual = parse(&ex1()).unwrap();
         assert_eq!(
             actual,
             Polymerization {
@@ -155,32 +178,20 @@ mod tests {
 
     // #[test]
     // fn test_step_ex1() {
-    //     let mut actual = parse(&ex1());
+    //     let mut actual = parse(&ex1()).unwrap();
     //     actual.step();
     //     assert_eq!(actual.polymer_template, "NCNBCHB");
     // }
 
-    #[test]
-    fn test_part1_ex1() {
-        let actual = part1(&parse(&ex1()));
-        assert_eq!(actual, 1588);
-    }
-
     #[test]
     fn test_part1_real() {
-        let actual = part1(&parse(&real()));
+        let actual = part1(&parse(&real()).unwrap());
         assert_eq!(actual, 2768);
     }
 
-    #[test]
-    fn test_part2_ex1() {
-        let actual = part2(&parse(&ex1()));
-        assert_eq!(actual, 2188189693529);
-    }
-
     #[test]
     fn test_part2_real() {
-        let actual = part2(&parse(&real()));
+        let actual = part2(&parse(&real()).unwrap());
         assert_eq!(actual, 2914365137499);
     }
 }
@@ -1,130 +1,230 @@
-use std::cmp::Ordering;
-use std::collections::BinaryHeap;
+use crate::error::ParseError;
+use crate::solution::Solution;
+use crate::util::search;
+use crate::util::{neighborhood, Edges, Grid, Neighborhood};
+use std::collections::HashSet;
+use std::fmt;
 
+/// A cave made of `tiles` by `tiles` copies of the scanned area. Each copy to the right or below
+/// has its risk levels one higher than the last, wrapping from 9 back to 1.
 #[derive(Debug, Eq, PartialEq)]
 pub struct Cave {
-    risk: Vec<Vec<usize>>,
+    risk: Grid<usize>,
+    tiles: usize,
 }
 
-// inspired from https://doc.rust-lang.org/std/collections/binary_heap/index.html
-#[derive(Copy, Clone, Eq, PartialEq)]
-struct State {
-    cost: usize,
-    position: (usize, usize),
-}
-
-impl Ord for State {
-    fn cmp(&self, other: &Self) -> Ordering {
-        other.cost.cmp(&self.cost)
+pub fn parse(input: &str) -> Result<Cave, ParseError> {
+    let risk = Grid::parse(15, input, "a risk level digit", |ch| {
+        ch.to_digit(10).map(|r| r as usize)
+    })?;
+    if risk.width() == 0 {
+        return Err(ParseError::new(15, 1, 1, "a risk level digit"));
     }
+    Ok(Cave { risk, tiles: 1 })
 }
 
-impl PartialOrd for State {
-    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
-        Some(self.cmp(other))
-    }
-}
+/// The positions from the top left to the bottom right corner of a cave, and their total risk.
+pub type Route = search::Path<(usize, usize), usize>;
 
-pub fn parse(input: &str) -> Cave {
-    let mut risk = vec![];
-    for line in input.lines() {
-        let mut risk_row = vec![];
-        for ch in line.chars() {
-            risk_row.push(ch as usize - '0' as usize);
+impl Cave {
+    /// The same scan repeated `tiles` times in both directions. Risk levels are worked out when
+    /// they're needed, so the tiles aren't stored.
+    pub fn tiled(&self, tiles: usize) -> Cave {
+        Cave {
+            risk: self.risk.clone(),
+            tiles,
         }
-        risk.push(risk_row);
     }
 
-    Cave { risk }
-}
-
-fn solve_maze(cave: &Cave) -> usize {
-    let start = (0, 0);
-    let goal = (cave.risk[0].len() - 1, cave.risk.len() - 1);
+    pub fn size(&self) -> (usize, usize) {
+        (
+            self.risk.width() * self.tiles,
+            self.risk.height() * self.tiles,
+        )
+    }
 
-    // dist[node] = current shortest distance from start to node
-    let mut dist: Vec<_> = cave
-        .risk
-        .iter()
-        .map(|row| row.iter().map(|_| usize::MAX).collect::<Vec<_>>())
-        .collect();
+    fn risk_at(&self, (x, y): (usize, usize)) -> usize {
+        let (width, height) = (self.risk.width(), self.risk.height());
+        let added_risk = x / width + y / height;
+        (self.risk[(x % width, y % height)] + added_risk - 1) % 9 + 1
+    }
 
-    let mut heap = BinaryHeap::new();
+    /// The positions one move away from `position`, each with its risk level.
+    fn moves_from<'a>(
+        &'a self,
+        position: (usize, usize),
+        moves: &'a Moves,
+    ) -> impl Iterator<Item = ((usize, usize), usize)> + 'a {
+        neighborhood(position, self.size(), moves.neighborhood, Edges::Clip)
+            .filter(|next| !moves.blocked.contains(next))
+            .map(|next| (next, self.risk_at(next)))
+    }
 
-    dist[start.1][start.0] = 0;
-    heap.push(State {
-        cost: 0,
-        position: start,
-    });
+    fn digit_at(&self, position: (usize, usize)) -> char {
+        char::from_digit(self.risk_at(position) as u32, 10).unwrap_or('?')
+    }
 
-    while let Some(State { cost, position }) = heap.pop() {
-        // found it!
-        if position == goal {
-            return cost;
+    /// The cave with the risk levels along `route` and a `.` everywhere else.
+    pub fn render_route(&self, route: &Route) -> String {
+        let (width, height) = self.size();
+        let mut map = Grid::new(width, height, '.');
+        for &position in &route.states {
+            map[position] = self.digit_at(position);
         }
+        map.to_string()
+    }
+}
 
-        if cost > dist[position.1][position.0] {
-            continue;
+impl fmt::Display for Cave {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let (width, height) = self.size();
+        for y in 0..height {
+            if y > 0 {
+                writeln!(f)?;
+            }
+            for x in 0..width {
+                write!(f, "{}", self.digit_at((x, y)))?;
+            }
         }
+        Ok(())
+    }
+}
 
-        let mut n = vec![];
-        if position.0 > 0 {
-            n.push((position.0 - 1, position.1));
-        }
-        if position.1 > 0 {
-            n.push((position.0, position.1 - 1));
-        }
-        if position.0 < goal.0 {
-            n.push((position.0 + 1, position.1));
-        }
-        if position.1 < goal.1 {
-            n.push((position.0, position.1 + 1));
+/// How a route may move through the cave.
+#[derive(Clone, Debug)]
+pub struct Moves {
+    /// `Neighborhood::All` allows diagonal moves.
+    pub neighborhood: Neighborhood,
+    /// Positions that can't be entered.
+    pub blocked: HashSet<(usize, usize)>,
+}
+
+impl Default for Moves {
+    fn default() -> Moves {
+        Moves {
+            neighborhood: Neighborhood::Orthogonal,
+            blocked: HashSet::new(),
         }
+    }
+}
 
-        n.iter().for_each(|&(x, y)| {
-            let next = State {
-                cost: cost + cave.risk[y][x],
-                position: (x, y),
-            };
-            if next.cost < dist[next.position.1][next.position.0] {
-                heap.push(next);
-                dist[next.position.1][next.position.0] = next.cost;
-            }
-        })
+/// The lowest total risk of reaching every position in the cave from one start, and the routes
+/// that do it.
+pub type RiskTable = search::Reachable<(usize, usize), usize>;
+
+fn in_cave(cave: &Cave, (x, y): (usize, usize)) -> bool {
+    let (width, height) = cave.size();
+    x < width && y < height
+}
+
+/// The lowest risk route from `start` to `goal`, or `None` if `goal` can't be reached.
+pub fn find_route(
+    cave: &Cave,
+    start: (usize, usize),
+    goal: (usize, usize),
+    moves: &Moves,
+) -> Option<Route> {
+    if !in_cave(cave, start) {
+        return None;
     }
+    let successors = |&position: &(usize, usize)| cave.moves_from(position, moves);
+    search::dijkstra(start, successors, |&position| position == goal)
+}
 
-    usize::MAX
+/// The lowest risk routes from `start` to every position that can be reached.
+pub fn risk_table(cave: &Cave, start: (usize, usize), moves: &Moves) -> Option<RiskTable> {
+    let successors = |&position: &(usize, usize)| cave.moves_from(position, moves);
+    in_cave(cave, start).then(|| search::dijkstra_all(start, successors))
 }
 
-pub fn part1(cave: &Cave) -> usize {
-    solve_maze(cave)
+/// The lowest risk route through the cave, or `None` if the bottom right can't be reached.
+pub fn solve_maze(cave: &Cave) -> Option<Route> {
+    let (width, height) = cave.size();
+    // an empty cave has no bottom right
+    let goal = (width.checked_sub(1)?, height.checked_sub(1)?);
+    find_route(cave, (0, 0), goal, &Moves::default())
 }
 
-fn expand(cave: &Cave) -> Cave {
-    let mut risk = vec![];
+pub fn part1(cave: &Cave) -> usize {
+    solve_maze(cave).map_or(usize::MAX, |route| route.cost)
+}
 
-    for tile_y in 0..5 {
-        for row in cave.risk.iter() {
-            let mut tiled_row = vec![];
-            for tile_x in 0..5 {
-                let added_risk = tile_y + tile_x;
+pub fn part2(cave: &Cave) -> usize {
+    solve_maze(&cave.tiled(5)).map_or(usize::MAX, |route| route.cost)
+}
 
-                for cell_risk in row.iter() {
-                    let new_risk = (cell_risk + added_risk - 1) % 9 + 1;
+/// The lowest total risk of reaching each position, in columns, with a `.` where it can't be
+/// reached.
+fn render_risks(cave: &Cave, table: &RiskTable) -> String {
+    let (width, height) = cave.size();
+    let risks: Vec<Vec<String>> = (0..height)
+        .map(|y| {
+            (0..width)
+                .map(|x| {
+                    table
+                        .cost(&(x, y))
+                        .map_or(".".to_string(), |r| r.to_string())
+                })
+                .collect()
+        })
+        .collect();
+    let column = risks.iter().flatten().map(|r| r.len()).max().unwrap_or(0);
+    let rows: Vec<String> = risks
+        .iter()
+        .map(|row| {
+            let cells: Vec<String> = row.iter().map(|r| format!("{:>column$}", r)).collect();
+            cells.join(" ")
+        })
+        .collect();
+    rows.join("\n")
+}
 
-                    tiled_row.push(new_risk);
-                }
-            }
-            risk.push(tiled_row);
+/// The views of a cave for `--explain`: the lowest risk `route` through it or through the five by
+/// five `tiled-route` of part 2, or the lowest total `risks` of reaching every position.
+pub fn explain(cave: &Cave, what: &str) -> Result<String, String> {
+    let route = |cave: &Cave| match solve_maze(cave) {
+        Some(route) => format!("{}\ntotal risk: {}", cave.render_route(&route), route.cost),
+        None => "There is no route through the cave".to_string(),
+    };
+    match what {
+        "route" => Ok(route(cave)),
+        "tiled-route" => Ok(route(&cave.tiled(5))),
+        "risks" => {
+            let table = risk_table(cave, (0, 0), &Moves::default());
+            Ok(table.map_or(String::new(), |table| render_risks(cave, &table)))
         }
+        _ => Err(format!(
+            "Unknown view '{}' of day 15, expected route, tiled-route or risks",
+            what
+        )),
     }
-
-    Cave { risk }
 }
 
-pub fn part2(cave: &Cave) -> usize {
-    let cave = expand(cave);
-    solve_maze(&cave)
+pub struct Day15;
+
+impl Solution for Day15 {
+    type Input = Cave;
+    type Output1 = usize;
+    type Output2 = usize;
+
+    const DAY: i32 = 15;
+    const TITLE: &'static str = "Chiton";
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        parse(input)
+    }
+
+    fn part1(input: &Self::Input) -> Self::Output1 {
+        part1(input)
+    }
+
+    fn part2(input: &Self::Input) -> Self::Output2 {
+        part2(input)
+    }
+
+    fn explain(input: &Self::Input, what: &str) -> Result<String, String> {
+        explain(input, what)
+    }
 }
 
 #[cfg(test)]
@@ -133,19 +233,7 @@ mod tests {
     use crate::util;
 
     fn ex1() -> String {
-        vec![
-            "1163751742",
-            "1381373672",
-            "2136511328",
-            "3694931569",
-            "7463417111",
-            "1319128137",
-            "1359912421",
-            "3125421639",
-            "1293138521",
-            "2311944581",
-        ]
-        .join("\n")
+        util::read_example(15, 1)
     }
 
     fn real() -> String {
@@ -154,7 +242,7 @@ mod tests {
 
     #[test]
     fn test_parse_ex1() {
-        let actual = parse(&ex1());
+        let actual = parse(&ex1()).unwrap();
         assert_eq!(
             actual,
             Cave {
@@ -170,243 +258,336 @@ mod tests {
                     vec![1, 2, 9, 3, 1, 3, 8, 5, 2, 1],
                     vec![2, 3, 1, 1, 9, 4, 4, 5, 8, 1],
                 ]
+                .into(),
+                tiles: 1,
             }
         )
     }
 
     #[test]
-    fn test_expand() {
-        let actual = expand(&parse(&ex1()));
+    fn test_parse_empty() {
+        let expected = Err(ParseError::new(15, 1, 1, "a risk level digit"));
+        assert_eq!(parse(""), expected);
+        assert_eq!(parse("\n"), expected);
+    }
+
+    #[test]
+    fn test_tiled() {
+        let actual = parse(&ex1()).unwrap().tiled(5);
+        assert_eq!(actual.size(), (50, 50));
+        let expected: Grid<usize> = vec![
+            vec![
+                1, 1, 6, 3, 7, 5, 1, 7, 4, 2, 2, 2, 7, 4, 8, 6, 2, 8, 5, 3, 3, 3, 8, 5, 9, 7, 3, 9,
+                6, 4, 4, 4, 9, 6, 1, 8, 4, 1, 7, 5, 5, 5, 1, 7, 2, 9, 5, 2, 8, 6,
+            ],
+            vec![
+                1, 3, 8, 1, 3, 7, 3, 6, 7, 2, 2, 4, 9, 2, 4, 8, 4, 7, 8, 3, 3, 5, 1, 3, 5, 9, 5, 8,
+                9, 4, 4, 6, 2, 4, 6, 1, 6, 9, 1, 5, 5, 7, 3, 5, 7, 2, 7, 1, 2, 6,
+            ],
+            vec![
+                2, 1, 3, 6, 5, 1, 1, 3, 2, 8, 3, 2, 4, 7, 6, 2, 2, 4, 3, 9, 4, 3, 5, 8, 7, 3, 3, 5,
+                4, 1, 5, 4, 6, 9, 8, 4, 4, 6, 5, 2, 6, 5, 7, 1, 9, 5, 5, 7, 6, 3,
+            ],
+            vec![
+                3, 6, 9, 4, 9, 3, 1, 5, 6, 9, 4, 7, 1, 5, 1, 4, 2, 6, 7, 1, 5, 8, 2, 6, 2, 5, 3, 7,
+                8, 2, 6, 9, 3, 7, 3, 6, 4, 8, 9, 3, 7, 1, 4, 8, 4, 7, 5, 9, 1, 4,
+            ],
+            vec![
+                7, 4, 6, 3, 4, 1, 7, 1, 1, 1, 8, 5, 7, 4, 5, 2, 8, 2, 2, 2, 9, 6, 8, 5, 6, 3, 9, 3,
+                3, 3, 1, 7, 9, 6, 7, 4, 1, 4, 4, 4, 2, 8, 1, 7, 8, 5, 2, 5, 5, 5,
+            ],
+            vec![
+                1, 3, 1, 9, 1, 2, 8, 1, 3, 7, 2, 4, 2, 1, 2, 3, 9, 2, 4, 8, 3, 5, 3, 2, 3, 4, 1, 3,
+                5, 9, 4, 6, 4, 3, 4, 5, 2, 4, 6, 1, 5, 7, 5, 4, 5, 6, 3, 5, 7, 2,
+            ],
+            vec![
+                1, 3, 5, 9, 9, 1, 2, 4, 2, 1, 2, 4, 6, 1, 1, 2, 3, 5, 3, 2, 3, 5, 7, 2, 2, 3, 4, 6,
+                4, 3, 4, 6, 8, 3, 3, 4, 5, 7, 5, 4, 5, 7, 9, 4, 4, 5, 6, 8, 6, 5,
+            ],
+            vec![
+                3, 1, 2, 5, 4, 2, 1, 6, 3, 9, 4, 2, 3, 6, 5, 3, 2, 7, 4, 1, 5, 3, 4, 7, 6, 4, 3, 8,
+                5, 2, 6, 4, 5, 8, 7, 5, 4, 9, 6, 3, 7, 5, 6, 9, 8, 6, 5, 1, 7, 4,
+            ],
+            vec![
+                1, 2, 9, 3, 1, 3, 8, 5, 2, 1, 2, 3, 1, 4, 2, 4, 9, 6, 3, 2, 3, 4, 2, 5, 3, 5, 1, 7,
+                4, 3, 4, 5, 3, 6, 4, 6, 2, 8, 5, 4, 5, 6, 4, 7, 5, 7, 3, 9, 6, 5,
+            ],
+            vec![
+                2, 3, 1, 1, 9, 4, 4, 5, 8, 1, 3, 4, 2, 2, 1, 5, 5, 6, 9, 2, 4, 5, 3, 3, 2, 6, 6, 7,
+                1, 3, 5, 6, 4, 4, 3, 7, 7, 8, 2, 4, 6, 7, 5, 5, 4, 8, 8, 9, 3, 5,
+            ],
+            vec![
+                2, 2, 7, 4, 8, 6, 2, 8, 5, 3, 3, 3, 8, 5, 9, 7, 3, 9, 6, 4, 4, 4, 9, 6, 1, 8, 4, 1,
+                7, 5, 5, 5, 1, 7, 2, 9, 5, 2, 8, 6, 6, 6, 2, 8, 3, 1, 6, 3, 9, 7,
+            ],
+            vec![
+                2, 4, 9, 2, 4, 8, 4, 7, 8, 3, 3, 5, 1, 3, 5, 9, 5, 8, 9, 4, 4, 6, 2, 4, 6, 1, 6, 9,
+                1, 5, 5, 7, 3, 5, 7, 2, 7, 1, 2, 6, 6, 8, 4, 6, 8, 3, 8, 2, 3, 7,
+            ],
+            vec![
+                3, 2, 4, 7, 6, 2, 2, 4, 3, 9, 4, 3, 5, 8, 7, 3, 3, 5, 4, 1, 5, 4, 6, 9, 8, 4, 4, 6,
+                5, 2, 6, 5, 7, 1, 9, 5, 5, 7, 6, 3, 7, 6, 8, 2, 1, 6, 6, 8, 7, 4,
+            ],
+            vec![
+                4, 7, 1, 5, 1, 4, 2, 6, 7, 1, 5, 8, 2, 6, 2, 5, 3, 7, 8, 2, 6, 9, 3, 7, 3, 6, 4, 8,
+                9, 3, 7, 1, 4, 8, 4, 7, 5, 9, 1, 4, 8, 2, 5, 9, 5, 8, 6, 1, 2, 5,
+            ],
+            vec![
+                8, 5, 7, 4, 5, 2, 8, 2, 2, 2, 9, 6, 8, 5, 6, 3, 9, 3, 3, 3, 1, 7, 9, 6, 7, 4, 1, 4,
+                4, 4, 2, 8, 1, 7, 8, 5, 2, 5, 5, 5, 3, 9, 2, 8, 9, 6, 3, 6, 6, 6,
+            ],
+            vec![
+                2, 4, 2, 1, 2, 3, 9, 2, 4, 8, 3, 5, 3, 2, 3, 4, 1, 3, 5, 9, 4, 6, 4, 3, 4, 5, 2, 4,
+                6, 1, 5, 7, 5, 4, 5, 6, 3, 5, 7, 2, 6, 8, 6, 5, 6, 7, 4, 6, 8, 3,
+            ],
+            vec![
+                2, 4, 6, 1, 1, 2, 3, 5, 3, 2, 3, 5, 7, 2, 2, 3, 4, 6, 4, 3, 4, 6, 8, 3, 3, 4, 5, 7,
+                5, 4, 5, 7, 9, 4, 4, 5, 6, 8, 6, 5, 6, 8, 1, 5, 5, 6, 7, 9, 7, 6,
+            ],
+            vec![
+                4, 2, 3, 6, 5, 3, 2, 7, 4, 1, 5, 3, 4, 7, 6, 4, 3, 8, 5, 2, 6, 4, 5, 8, 7, 5, 4, 9,
+                6, 3, 7, 5, 6, 9, 8, 6, 5, 1, 7, 4, 8, 6, 7, 1, 9, 7, 6, 2, 8, 5,
+            ],
+            vec![
+                2, 3, 1, 4, 2, 4, 9, 6, 3, 2, 3, 4, 2, 5, 3, 5, 1, 7, 4, 3, 4, 5, 3, 6, 4, 6, 2, 8,
+                5, 4, 5, 6, 4, 7, 5, 7, 3, 9, 6, 5, 6, 7, 5, 8, 6, 8, 4, 1, 7, 6,
+            ],
+            vec![
+                3, 4, 2, 2, 1, 5, 5, 6, 9, 2, 4, 5, 3, 3, 2, 6, 6, 7, 1, 3, 5, 6, 4, 4, 3, 7, 7, 8,
+                2, 4, 6, 7, 5, 5, 4, 8, 8, 9, 3, 5, 7, 8, 6, 6, 5, 9, 9, 1, 4, 6,
+            ],
+            vec![
+                3, 3, 8, 5, 9, 7, 3, 9, 6, 4, 4, 4, 9, 6, 1, 8, 4, 1, 7, 5, 5, 5, 1, 7, 2, 9, 5, 2,
+                8, 6, 6, 6, 2, 8, 3, 1, 6, 3, 9, 7, 7, 7, 3, 9, 4, 2, 7, 4, 1, 8,
+            ],
+            vec![
+                3, 5, 1, 3, 5, 9, 5, 8, 9, 4, 4, 6, 2, 4, 6, 1, 6, 9, 1, 5, 5, 7, 3, 5, 7, 2, 7, 1,
+                2, 6, 6, 8, 4, 6, 8, 3, 8, 2, 3, 7, 7, 9, 5, 7, 9, 4, 9, 3, 4, 8,
+            ],
+            vec![
+                4, 3, 5, 8, 7, 3, 3, 5, 4, 1, 5, 4, 6, 9, 8, 4, 4, 6, 5, 2, 6, 5, 7, 1, 9, 5, 5, 7,
+                6, 3, 7, 6, 8, 2, 1, 6, 6, 8, 7, 4, 8, 7, 9, 3, 2, 7, 7, 9, 8, 5,
+            ],
+            vec![
+                5, 8, 2, 6, 2, 5, 3, 7, 8, 2, 6, 9, 3, 7, 3, 6, 4, 8, 9, 3, 7, 1, 4, 8, 4, 7, 5, 9,
+                1, 4, 8, 2, 5, 9, 5, 8, 6, 1, 2, 5, 9, 3, 6, 1, 6, 9, 7, 2, 3, 6,
+            ],
+            vec![
+                9, 6, 8, 5, 6, 3, 9, 3, 3, 3, 1, 7, 9, 6, 7, 4, 1, 4, 4, 4, 2, 8, 1, 7, 8, 5, 2, 5,
+                5, 5, 3, 9, 2, 8, 9, 6, 3, 6, 6, 6, 4, 1, 3, 9, 1, 7, 4, 7, 7, 7,
+            ],
+            vec![
+                3, 5, 3, 2, 3, 4, 1, 3, 5, 9, 4, 6, 4, 3, 4, 5, 2, 4, 6, 1, 5, 7, 5, 4, 5, 6, 3, 5,
+                7, 2, 6, 8, 6, 5, 6, 7, 4, 6, 8, 3, 7, 9, 7, 6, 7, 8, 5, 7, 9, 4,
+            ],
+            vec![
+                3, 5, 7, 2, 2, 3, 4, 6, 4, 3, 4, 6, 8, 3, 3, 4, 5, 7, 5, 4, 5, 7, 9, 4, 4, 5, 6, 8,
+                6, 5, 6, 8, 1, 5, 5, 6, 7, 9, 7, 6, 7, 9, 2, 6, 6, 7, 8, 1, 8, 7,
+            ],
+            vec![
+                5, 3, 4, 7, 6, 4, 3, 8, 5, 2, 6, 4, 5, 8, 7, 5, 4, 9, 6, 3, 7, 5, 6, 9, 8, 6, 5, 1,
+                7, 4, 8, 6, 7, 1, 9, 7, 6, 2, 8, 5, 9, 7, 8, 2, 1, 8, 7, 3, 9, 6,
+            ],
+            vec![
+                3, 4, 2, 5, 3, 5, 1, 7, 4, 3, 4, 5, 3, 6, 4, 6, 2, 8, 5, 4, 5, 6, 4, 7, 5, 7, 3, 9,
+                6, 5, 6, 7, 5, 8, 6, 8, 4, 1, 7, 6, 7, 8, 6, 9, 7, 9, 5, 2, 8, 7,
+            ],
+            vec![
+                4, 5, 3, 3, 2, 6, 6, 7, 1, 3, 5, 6, 4, 4, 3, 7, 7, 8, 2, 4, 6, 7, 5, 5, 4, 8, 8, 9,
+                3, 5, 7, 8, 6, 6, 5, 9, 9, 1, 4, 6, 8, 9, 7, 7, 6, 1, 1, 2, 5, 7,
+            ],
+            vec![
+                4, 4, 9, 6, 1, 8, 4, 1, 7, 5, 5, 5, 1, 7, 2, 9, 5, 2, 8, 6, 6, 6, 2, 8, 3, 1, 6, 3,
+                9, 7, 7, 7, 3, 9, 4, 2, 7, 4, 1, 8, 8, 8, 4, 1, 5, 3, 8, 5, 2, 9,
+            ],
+            vec![
+                4, 6, 2, 4, 6, 1, 6, 9, 1, 5, 5, 7, 3, 5, 7, 2, 7, 1, 2, 6, 6, 8, 4, 6, 8, 3, 8, 2,
+                3, 7, 7, 9, 5, 7, 9, 4, 9, 3, 4, 8, 8, 1, 6, 8, 1, 5, 1, 4, 5, 9,
+            ],
+            vec![
+                5, 4, 6, 9, 8, 4, 4, 6, 5, 2, 6, 5, 7, 1, 9, 5, 5, 7, 6, 3, 7, 6, 8, 2, 1, 6, 6, 8,
+                7, 4, 8, 7, 9, 3, 2, 7, 7, 9, 8, 5, 9, 8, 1, 4, 3, 8, 8, 1, 9, 6,
+            ],
+            vec![
+                6, 9, 3, 7, 3, 6, 4, 8, 9, 3, 7, 1, 4, 8, 4, 7, 5, 9, 1, 4, 8, 2, 5, 9, 5, 8, 6, 1,
+                2, 5, 9, 3, 6, 1, 6, 9, 7, 2, 3, 6, 1, 4, 7, 2, 7, 1, 8, 3, 4, 7,
+            ],
+            vec![
+                1, 7, 9, 6, 7, 4, 1, 4, 4, 4, 2, 8, 1, 7, 8, 5, 2, 5, 5, 5, 3, 9, 2, 8, 9, 6, 3, 6,
+                6, 6, 4, 1, 3, 9, 1, 7, 4, 7, 7, 7, 5, 2, 4, 1, 2, 8, 5, 8, 8, 8,
+            ],
+            vec![
+                4, 6, 4, 3, 4, 5, 2, 4, 6, 1, 5, 7, 5, 4, 5, 6, 3, 5, 7, 2, 6, 8, 6, 5, 6, 7, 4, 6,
+                8, 3, 7, 9, 7, 6, 7, 8, 5, 7, 9, 4, 8, 1, 8, 7, 8, 9, 6, 8, 1, 5,
+            ],
+            vec![
+                4, 6, 8, 3, 3, 4, 5, 7, 5, 4, 5, 7, 9, 4, 4, 5, 6, 8, 6, 5, 6, 8, 1, 5, 5, 6, 7, 9,
+                7, 6, 7, 9, 2, 6, 6, 7, 8, 1, 8, 7, 8, 1, 3, 7, 7, 8, 9, 2, 9, 8,
+            ],
+            vec![
+                6, 4, 5, 8, 7, 5, 4, 9, 6, 3, 7, 5, 6, 9, 8, 6, 5, 1, 7, 4, 8, 6, 7, 1, 9, 7, 6, 2,
+                8, 5, 9, 7, 8, 2, 1, 8, 7, 3, 9, 6, 1, 8, 9, 3, 2, 9, 8, 4, 1, 7,
+            ],
+            vec![
+                4, 5, 3, 6, 4, 6, 2, 8, 5, 4, 5, 6, 4, 7, 5, 7, 3, 9, 6, 5, 6, 7, 5, 8, 6, 8, 4, 1,
+                7, 6, 7, 8, 6, 9, 7, 9, 5, 2, 8, 7, 8, 9, 7, 1, 8, 1, 6, 3, 9, 8,
+            ],
+            vec![
+                5, 6, 4, 4, 3, 7, 7, 8, 2, 4, 6, 7, 5, 5, 4, 8, 8, 9, 3, 5, 7, 8, 6, 6, 5, 9, 9, 1,
+                4, 6, 8, 9, 7, 7, 6, 1, 1, 2, 5, 7, 9, 1, 8, 8, 7, 2, 2, 3, 6, 8,
+            ],
+            vec![
+                5, 5, 1, 7, 2, 9, 5, 2, 8, 6, 6, 6, 2, 8, 3, 1, 6, 3, 9, 7, 7, 7, 3, 9, 4, 2, 7, 4,
+                1, 8, 8, 8, 4, 1, 5, 3, 8, 5, 2, 9, 9, 9, 5, 2, 6, 4, 9, 6, 3, 1,
+            ],
+            vec![
+                5, 7, 3, 5, 7, 2, 7, 1, 2, 6, 6, 8, 4, 6, 8, 3, 8, 2, 3, 7, 7, 9, 5, 7, 9, 4, 9, 3,
+                4, 8, 8, 1, 6, 8, 1, 5, 1, 4, 5, 9, 9, 2, 7, 9, 2, 6, 2, 5, 6, 1,
+            ],
+            vec![
+                6, 5, 7, 1, 9, 5, 5, 7, 6, 3, 7, 6, 8, 2, 1, 6, 6, 8, 7, 4, 8, 7, 9, 3, 2, 7, 7, 9,
+                8, 5, 9, 8, 1, 4, 3, 8, 8, 1, 9, 6, 1, 9, 2, 5, 4, 9, 9, 2, 1, 7,
+            ],
+            vec![
+                7, 1, 4, 8, 4, 7, 5, 9, 1, 4, 8, 2, 5, 9, 5, 8, 6, 1, 2, 5, 9, 3, 6, 1, 6, 9, 7, 2,
+                3, 6, 1, 4, 7, 2, 7, 1, 8, 3, 4, 7, 2, 5, 8, 3, 8, 2, 9, 4, 5, 8,
+            ],
+            vec![
+                2, 8, 1, 7, 8, 5, 2, 5, 5, 5, 3, 9, 2, 8, 9, 6, 3, 6, 6, 6, 4, 1, 3, 9, 1, 7, 4, 7,
+                7, 7, 5, 2, 4, 1, 2, 8, 5, 8, 8, 8, 6, 3, 5, 2, 3, 9, 6, 9, 9, 9,
+            ],
+            vec![
+                5, 7, 5, 4, 5, 6, 3, 5, 7, 2, 6, 8, 6, 5, 6, 7, 4, 6, 8, 3, 7, 9, 7, 6, 7, 8, 5, 7,
+                9, 4, 8, 1, 8, 7, 8, 9, 6, 8, 1, 5, 9, 2, 9, 8, 9, 1, 7, 9, 2, 6,
+            ],
+            vec![
+                5, 7, 9, 4, 4, 5, 6, 8, 6, 5, 6, 8, 1, 5, 5, 6, 7, 9, 7, 6, 7, 9, 2, 6, 6, 7, 8, 1,
+                8, 7, 8, 1, 3, 7, 7, 8, 9, 2, 9, 8, 9, 2, 4, 8, 8, 9, 1, 3, 1, 9,
+            ],
+            vec![
+                7, 5, 6, 9, 8, 6, 5, 1, 7, 4, 8, 6, 7, 1, 9, 7, 6, 2, 8, 5, 9, 7, 8, 2, 1, 8, 7, 3,
+                9, 6, 1, 8, 9, 3, 2, 9, 8, 4, 1, 7, 2, 9, 1, 4, 3, 1, 9, 5, 2, 8,
+            ],
+            vec![
+                5, 6, 4, 7, 5, 7, 3, 9, 6, 5, 6, 7, 5, 8, 6, 8, 4, 1, 7, 6, 7, 8, 6, 9, 7, 9, 5, 2,
+                8, 7, 8, 9, 7, 1, 8, 1, 6, 3, 9, 8, 9, 1, 8, 2, 9, 2, 7, 4, 1, 9,
+            ],
+            vec![
+                6, 7, 5, 5, 4, 8, 8, 9, 3, 5, 7, 8, 6, 6, 5, 9, 9, 1, 4, 6, 8, 9, 7, 7, 6, 1, 1, 2,
+                5, 7, 9, 1, 8, 8, 7, 2, 2, 3, 6, 8, 1, 2, 9, 9, 8, 3, 3, 4, 7, 9,
+            ],
+        ]
+        .into();
+        assert_eq!(actual.to_string(), expected.to_string());
+    }
+
+    #[test]
+    fn test_tiled_wraps() {
+        let cave = parse("8").unwrap().tiled(3);
+        assert_eq!(cave.to_string(), ["891", "912", "123"].join("\n"));
+    }
+
+    #[test]
+    fn test_solve_maze_ex1() {
+        let cave = parse(&ex1()).unwrap();
+        let route = solve_maze(&cave).unwrap();
+        assert_eq!(route.states.first(), Some(&(0, 0)));
+        assert_eq!(route.states.last(), Some(&(9, 9)));
+        for step in route.states.windows(2) {
+            let ((x1, y1), (x2, y2)) = (step[0], step[1]);
+            assert_eq!(x1.abs_diff(x2) + y1.abs_diff(y2), 1, "{:?}", step);
+        }
+        let risk: usize = route.states[1..].iter().map(|&p| cave.risk_at(p)).sum();
+        assert_eq!(risk, route.cost);
+        assert_eq!(route.cost, 40);
+    }
+
+    #[test]
+    fn test_solve_maze_no_tiles() {
+        let cave = parse("1").unwrap().tiled(0);
+        assert_eq!(cave.size(), (0, 0));
+        assert_eq!(solve_maze(&cave), None);
+    }
+
+    #[test]
+    fn test_render_route() {
+        let cave = parse(&["1999", "1111", "9991"].join("\n")).unwrap();
+        let route = solve_maze(&cave).unwrap();
+        assert_eq!(route.cost, 5);
         assert_eq!(
-            actual,
-            Cave {
-                risk: vec![
-                    vec![
-                        1, 1, 6, 3, 7, 5, 1, 7, 4, 2, 2, 2, 7, 4, 8, 6, 2, 8, 5, 3, 3, 3, 8, 5, 9,
-                        7, 3, 9, 6, 4, 4, 4, 9, 6, 1, 8, 4, 1, 7, 5, 5, 5, 1, 7, 2, 9, 5, 2, 8, 6
-                    ],
-                    vec![
-                        1, 3, 8, 1, 3, 7, 3, 6, 7, 2, 2, 4, 9, 2, 4, 8, 4, 7, 8, 3, 3, 5, 1, 3, 5,
-                        9, 5, 8, 9, 4, 4, 6, 2, 4, 6, 1, 6, 9, 1, 5, 5, 7, 3, 5, 7, 2, 7, 1, 2, 6
-                    ],
-                    vec![
-                        2, 1, 3, 6, 5, 1, 1, 3, 2, 8, 3, 2, 4, 7, 6, 2, 2, 4, 3, 9, 4, 3, 5, 8, 7,
-                        3, 3, 5, 4, 1, 5, 4, 6, 9, 8, 4, 4, 6, 5, 2, 6, 5, 7, 1, 9, 5, 5, 7, 6, 3
-                    ],
-                    vec![
-                        3, 6, 9, 4, 9, 3, 1, 5, 6, 9, 4, 7, 1, 5, 1, 4, 2, 6, 7, 1, 5, 8, 2, 6, 2,
-                        5, 3, 7, 8, 2, 6, 9, 3, 7, 3, 6, 4, 8, 9, 3, 7, 1, 4, 8, 4, 7, 5, 9, 1, 4
-                    ],
-                    vec![
-                        7, 4, 6, 3, 4, 1, 7, 1, 1, 1, 8, 5, 7, 4, 5, 2, 8, 2, 2, 2, 9, 6, 8, 5, 6,
-                        3, 9, 3, 3, 3, 1, 7, 9, 6, 7, 4, 1, 4, 4, 4, 2, 8, 1, 7, 8, 5, 2, 5, 5, 5
-                    ],
-                    vec![
-                        1, 3, 1, 9, 1, 2, 8, 1, 3, 7, 2, 4, 2, 1, 2, 3, 9, 2, 4, 8, 3, 5, 3, 2, 3,
-                        4, 1, 3, 5, 9, 4, 6, 4, 3, 4, 5, 2, 4, 6, 1, 5, 7, 5, 4, 5, 6, 3, 5, 7, 2
-                    ],
-                    vec![
-                        1, 3, 5, 9, 9, 1, 2, 4, 2, 1, 2, 4, 6, 1, 1, 2, 3, 5, 3, 2, 3, 5, 7, 2, 2,
-                        3, 4, 6, 4, 3, 4, 6, 8, 3, 3, 4, 5, 7, 5, 4, 5, 7, 9, 4, 4, 5, 6, 8, 6, 5
-                    ],
-                    vec![
-                        3, 1, 2, 5, 4, 2, 1, 6, 3, 9, 4, 2, 3, 6, 5, 3, 2, 7, 4, 1, 5, 3, 4, 7, 6,
-                        4, 3, 8, 5, 2, 6, 4, 5, 8, 7, 5, 4, 9, 6, 3, 7, 5, 6, 9, 8, 6, 5, 1, 7, 4
-                    ],
-                    vec![
-                        1, 2, 9, 3, 1, 3, 8, 5, 2, 1, 2, 3, 1, 4, 2, 4, 9, 6, 3, 2, 3, 4, 2, 5, 3,
-                        5, 1, 7, 4, 3, 4, 5, 3, 6, 4, 6, 2, 8, 5, 4, 5, 6, 4, 7, 5, 7, 3, 9, 6, 5
-                    ],
-                    vec![
-                        2, 3, 1, 1, 9, 4, 4, 5, 8, 1, 3, 4, 2, 2, 1, 5, 5, 6, 9, 2, 4, 5, 3, 3, 2,
-                        6, 6, 7, 1, 3, 5, 6, 4, 4, 3, 7, 7, 8, 2, 4, 6, 7, 5, 5, 4, 8, 8, 9, 3, 5
-                    ],
-                    vec![
-                        2, 2, 7, 4, 8, 6, 2, 8, 5, 3, 3, 3, 8, 5, 9, 7, 3, 9, 6, 4, 4, 4, 9, 6, 1,
-                        8, 4, 1, 7, 5, 5, 5, 1, 7, 2, 9, 5, 2, 8, 6, 6, 6, 2, 8, 3, 1, 6, 3, 9, 7
-                    ],
-                    vec![
-                        2, 4, 9, 2, 4, 8, 4, 7, 8, 3, 3, 5, 1, 3, 5, 9, 5, 8, 9, 4, 4, 6, 2, 4, 6,
-                        1, 6, 9, 1, 5, 5, 7, 3, 5, 7, 2, 7, 1, 2, 6, 6, 8, 4, 6, 8, 3, 8, 2, 3, 7
-                    ],
-                    vec![
-                        3, 2, 4, 7, 6, 2, 2, 4, 3, 9, 4, 3, 5, 8, 7, 3, 3, 5, 4, 1, 5, 4, 6, 9, 8,
-                        4, 4, 6, 5, 2, 6, 5, 7, 1, 9, 5, 5, 7, 6, 3, 7, 6, 8, 2, 1, 6, 6, 8, 7, 4
-                    ],
-                    vec![
-                        4, 7, 1, 5, 1, 4, 2, 6, 7, 1, 5, 8, 2, 6, 2, 5, 3, 7, 8, 2, 6, 9, 3, 7, 3,
-                        6, 4, 8, 9, 3, 7, 1, 4, 8, 4, 7, 5, 9, 1, 4, 8, 2, 5, 9, 5, 8, 6, 1, 2, 5
-                    ],
-                    vec![
-                        8, 5, 7, 4, 5, 2, 8, 2, 2, 2, 9, 6, 8, 5, 6, 3, 9, 3, 3, 3, 1, 7, 9, 6, 7,
-                        4, 1, 4, 4, 4, 2, 8, 1, 7, 8, 5, 2, 5, 5, 5, 3, 9, 2, 8, 9, 6, 3, 6, 6, 6
-                    ],
-                    vec![
-                        2, 4, 2, 1, 2, 3, 9, 2, 4, 8, 3, 5, 3, 2, 3, 4, 1, 3, 5, 9, 4, 6, 4, 3, 4,
-                        5, 2, 4, 6, 1, 5, 7, 5, 4, 5, 6, 3, 5, 7, 2, 6, 8, 6, 5, 6, 7, 4, 6, 8, 3
-                    ],
-                    vec![
-                        2, 4, 6, 1, 1, 2, 3, 5, 3, 2, 3, 5, 7, 2, 2, 3, 4, 6, 4, 3, 4, 6, 8, 3, 3,
-                        4, 5, 7, 5, 4, 5, 7, 9, 4, 4, 5, 6, 8, 6, 5, 6, 8, 1, 5, 5, 6, 7, 9, 7, 6
-                    ],
-                    vec![
-                        4, 2, 3, 6, 5, 3, 2, 7, 4, 1, 5, 3, 4, 7, 6, 4, 3, 8, 5, 2, 6, 4, 5, 8, 7,
-                        5, 4, 9, 6, 3, 7, 5, 6, 9, 8, 6, 5, 1, 7, 4, 8, 6, 7, 1, 9, 7, 6, 2, 8, 5
-                    ],
-                    vec![
-                        2, 3, 1, 4, 2, 4, 9, 6, 3, 2, 3, 4, 2, 5, 3, 5, 1, 7, 4, 3, 4, 5, 3, 6, 4,
-                        6, 2, 8, 5, 4, 5, 6, 4, 7, 5, 7, 3, 9, 6, 5, 6, 7, 5, 8, 6, 8, 4, 1, 7, 6
-                    ],
-                    vec![
-                        3, 4, 2, 2, 1, 5, 5, 6, 9, 2, 4, 5, 3, 3, 2, 6, 6, 7, 1, 3, 5, 6, 4, 4, 3,
-                        7, 7, 8, 2, 4, 6, 7, 5, 5, 4, 8, 8, 9, 3, 5, 7, 8, 6, 6, 5, 9, 9, 1, 4, 6
-                    ],
-                    vec![
-                        3, 3, 8, 5, 9, 7, 3, 9, 6, 4, 4, 4, 9, 6, 1, 8, 4, 1, 7, 5, 5, 5, 1, 7, 2,
-                        9, 5, 2, 8, 6, 6, 6, 2, 8, 3, 1, 6, 3, 9, 7, 7, 7, 3, 9, 4, 2, 7, 4, 1, 8
-                    ],
-                    vec![
-                        3, 5, 1, 3, 5, 9, 5, 8, 9, 4, 4, 6, 2, 4, 6, 1, 6, 9, 1, 5, 5, 7, 3, 5, 7,
-                        2, 7, 1, 2, 6, 6, 8, 4, 6, 8, 3, 8, 2, 3, 7, 7, 9, 5, 7, 9, 4, 9, 3, 4, 8
-                    ],
-                    vec![
-                        4, 3, 5, 8, 7, 3, 3, 5, 4, 1, 5, 4, 6, 9, 8, 4, 4, 6, 5, 2, 6, 5, 7, 1, 9,
-                        5, 5, 7, 6, 3, 7, 6, 8, 2, 1, 6, 6, 8, 7, 4, 8, 7, 9, 3, 2, 7, 7, 9, 8, 5
-                    ],
-                    vec![
-                        5, 8, 2, 6, 2, 5, 3, 7, 8, 2, 6, 9, 3, 7, 3, 6, 4, 8, 9, 3, 7, 1, 4, 8, 4,
-                        7, 5, 9, 1, 4, 8, 2, 5, 9, 5, 8, 6, 1, 2, 5, 9, 3, 6, 1, 6, 9, 7, 2, 3, 6
-                    ],
-                    vec![
-                        9, 6, 8, 5, 6, 3, 9, 3, 3, 3, 1, 7, 9, 6, 7, 4, 1, 4, 4, 4, 2, 8, 1, 7, 8,
-                        5, 2, 5, 5, 5, 3, 9, 2, 8, 9, 6, 3, 6, 6, 6, 4, 1, 3, 9, 1, 7, 4, 7, 7, 7
-                    ],
-                    vec![
-                        3, 5, 3, 2, 3, 4, 1, 3, 5, 9, 4, 6, 4, 3, 4, 5, 2, 4, 6, 1, 5, 7, 5, 4, 5,
-                        6, 3, 5, 7, 2, 6, 8, 6, 5, 6, 7, 4, 6, 8, 3, 7, 9, 7, 6, 7, 8, 5, 7, 9, 4
-                    ],
-                    vec![
-                        3, 5, 7, 2, 2, 3, 4, 6, 4, 3, 4, 6, 8, 3, 3, 4, 5, 7, 5, 4, 5, 7, 9, 4, 4,
-                        5, 6, 8, 6, 5, 6, 8, 1, 5, 5, 6, 7, 9, 7, 6, 7, 9, 2, 6, 6, 7, 8, 1, 8, 7
-                    ],
-                    vec![
-                        5, 3, 4, 7, 6, 4, 3, 8, 5, 2, 6, 4, 5, 8, 7, 5, 4, 9, 6, 3, 7, 5, 6, 9, 8,
-                        6, 5, 1, 7, 4, 8, 6, 7, 1, 9, 7, 6, 2, 8, 5, 9, 7, 8, 2, 1, 8, 7, 3, 9, 6
-                    ],
-                    vec![
-                        3, 4, 2, 5, 3, 5, 1, 7, 4, 3, 4, 5, 3, 6, 4, 6, 2, 8, 5, 4, 5, 6, 4, 7, 5,
-                        7, 3, 9, 6, 5, 6, 7, 5, 8, 6, 8, 4, 1, 7, 6, 7, 8, 6, 9, 7, 9, 5, 2, 8, 7
-                    ],
-                    vec![
-                        4, 5, 3, 3, 2, 6, 6, 7, 1, 3, 5, 6, 4, 4, 3, 7, 7, 8, 2, 4, 6, 7, 5, 5, 4,
-                        8, 8, 9, 3, 5, 7, 8, 6, 6, 5, 9, 9, 1, 4, 6, 8, 9, 7, 7, 6, 1, 1, 2, 5, 7
-                    ],
-                    vec![
-                        4, 4, 9, 6, 1, 8, 4, 1, 7, 5, 5, 5, 1, 7, 2, 9, 5, 2, 8, 6, 6, 6, 2, 8, 3,
-                        1, 6, 3, 9, 7, 7, 7, 3, 9, 4, 2, 7, 4, 1, 8, 8, 8, 4, 1, 5, 3, 8, 5, 2, 9
-                    ],
-                    vec![
-                        4, 6, 2, 4, 6, 1, 6, 9, 1, 5, 5, 7, 3, 5, 7, 2, 7, 1, 2, 6, 6, 8, 4, 6, 8,
-                        3, 8, 2, 3, 7, 7, 9, 5, 7, 9, 4, 9, 3, 4, 8, 8, 1, 6, 8, 1, 5, 1, 4, 5, 9
-                    ],
-                    vec![
-                        5, 4, 6, 9, 8, 4, 4, 6, 5, 2, 6, 5, 7, 1, 9, 5, 5, 7, 6, 3, 7, 6, 8, 2, 1,
-                        6, 6, 8, 7, 4, 8, 7, 9, 3, 2, 7, 7, 9, 8, 5, 9, 8, 1, 4, 3, 8, 8, 1, 9, 6
-                    ],
-                    vec![
-                        6, 9, 3, 7, 3, 6, 4, 8, 9, 3, 7, 1, 4, 8, 4, 7, 5, 9, 1, 4, 8, 2, 5, 9, 5,
-                        8, 6, 1, 2, 5, 9, 3, 6, 1, 6, 9, 7, 2, 3, 6, 1, 4, 7, 2, 7, 1, 8, 3, 4, 7
-                    ],
-                    vec![
-                        1, 7, 9, 6, 7, 4, 1, 4, 4, 4, 2, 8, 1, 7, 8, 5, 2, 5, 5, 5, 3, 9, 2, 8, 9,
-                        6, 3, 6, 6, 6, 4, 1, 3, 9, 1, 7, 4, 7, 7, 7, 5, 2, 4, 1, 2, 8, 5, 8, 8, 8
-                    ],
-                    vec![
-                        4, 6, 4, 3, 4, 5, 2, 4, 6, 1, 5, 7, 5, 4, 5, 6, 3, 5, 7, 2, 6, 8, 6, 5, 6,
-                        7, 4, 6, 8, 3, 7, 9, 7, 6, 7, 8, 5, 7, 9, 4, 8, 1, 8, 7, 8, 9, 6, 8, 1, 5
-                    ],
-                    vec![
-                        4, 6, 8, 3, 3, 4, 5, 7, 5, 4, 5, 7, 9, 4, 4, 5, 6, 8, 6, 5, 6, 8, 1, 5, 5,
-                        6, 7, 9, 7, 6, 7, 9, 2, 6, 6, 7, 8, 1, 8, 7, 8, 1, 3, 7, 7, 8, 9, 2, 9, 8
-                    ],
-                    vec![
-                        6, 4, 5, 8, 7, 5, 4, 9, 6, 3, 7, 5, 6, 9, 8, 6, 5, 1, 7, 4, 8, 6, 7, 1, 9,
-                        7, 6, 2, 8, 5, 9, 7, 8, 2, 1, 8, 7, 3, 9, 6, 1, 8, 9, 3, 2, 9, 8, 4, 1, 7
-                    ],
-                    vec![
-                        4, 5, 3, 6, 4, 6, 2, 8, 5, 4, 5, 6, 4, 7, 5, 7, 3, 9, 6, 5, 6, 7, 5, 8, 6,
-                        8, 4, 1, 7, 6, 7, 8, 6, 9, 7, 9, 5, 2, 8, 7, 8, 9, 7, 1, 8, 1, 6, 3, 9, 8
-                    ],
-                    vec![
-                        5, 6, 4, 4, 3, 7, 7, 8, 2, 4, 6, 7, 5, 5, 4, 8, 8, 9, 3, 5, 7, 8, 6, 6, 5,
-                        9, 9, 1, 4, 6, 8, 9, 7, 7, 6, 1, 1, 2, 5, 7, 9, 1, 8, 8, 7, 2, 2, 3, 6, 8
-                    ],
-                    vec![
-                        5, 5, 1, 7, 2, 9, 5, 2, 8, 6, 6, 6, 2, 8, 3, 1, 6, 3, 9, 7, 7, 7, 3, 9, 4,
-                        2, 7, 4, 1, 8, 8, 8, 4, 1, 5, 3, 8, 5, 2, 9, 9, 9, 5, 2, 6, 4, 9, 6, 3, 1
-                    ],
-                    vec![
-                        5, 7, 3, 5, 7, 2, 7, 1, 2, 6, 6, 8, 4, 6, 8, 3, 8, 2, 3, 7, 7, 9, 5, 7, 9,
-                        4, 9, 3, 4, 8, 8, 1, 6, 8, 1, 5, 1, 4, 5, 9, 9, 2, 7, 9, 2, 6, 2, 5, 6, 1
-                    ],
-                    vec![
-                        6, 5, 7, 1, 9, 5, 5, 7, 6, 3, 7, 6, 8, 2, 1, 6, 6, 8, 7, 4, 8, 7, 9, 3, 2,
-                        7, 7, 9, 8, 5, 9, 8, 1, 4, 3, 8, 8, 1, 9, 6, 1, 9, 2, 5, 4, 9, 9, 2, 1, 7
-                    ],
-                    vec![
-                        7, 1, 4, 8, 4, 7, 5, 9, 1, 4, 8, 2, 5, 9, 5, 8, 6, 1, 2, 5, 9, 3, 6, 1, 6,
-                        9, 7, 2, 3, 6, 1, 4, 7, 2, 7, 1, 8, 3, 4, 7, 2, 5, 8, 3, 8, 2, 9, 4, 5, 8
-                    ],
-                    vec![
-                        2, 8, 1, 7, 8, 5, 2, 5, 5, 5, 3, 9, 2, 8, 9, 6, 3, 6, 6, 6, 4, 1, 3, 9, 1,
-                        7, 4, 7, 7, 7, 5, 2, 4, 1, 2, 8, 5, 8, 8, 8, 6, 3, 5, 2, 3, 9, 6, 9, 9, 9
-                    ],
-                    vec![
-                        5, 7, 5, 4, 5, 6, 3, 5, 7, 2, 6, 8, 6, 5, 6, 7, 4, 6, 8, 3, 7, 9, 7, 6, 7,
-                        8, 5, 7, 9, 4, 8, 1, 8, 7, 8, 9, 6, 8, 1, 5, 9, 2, 9, 8, 9, 1, 7, 9, 2, 6
-                    ],
-                    vec![
-                        5, 7, 9, 4, 4, 5, 6, 8, 6, 5, 6, 8, 1, 5, 5, 6, 7, 9, 7, 6, 7, 9, 2, 6, 6,
-                        7, 8, 1, 8, 7, 8, 1, 3, 7, 7, 8, 9, 2, 9, 8, 9, 2, 4, 8, 8, 9, 1, 3, 1, 9
-                    ],
-                    vec![
-                        7, 5, 6, 9, 8, 6, 5, 1, 7, 4, 8, 6, 7, 1, 9, 7, 6, 2, 8, 5, 9, 7, 8, 2, 1,
-                        8, 7, 3, 9, 6, 1, 8, 9, 3, 2, 9, 8, 4, 1, 7, 2, 9, 1, 4, 3, 1, 9, 5, 2, 8
-                    ],
-                    vec![
-                        5, 6, 4, 7, 5, 7, 3, 9, 6, 5, 6, 7, 5, 8, 6, 8, 4, 1, 7, 6, 7, 8, 6, 9, 7,
-                        9, 5, 2, 8, 7, 8, 9, 7, 1, 8, 1, 6, 3, 9, 8, 9, 1, 8, 2, 9, 2, 7, 4, 1, 9
-                    ],
-                    vec![
-                        6, 7, 5, 5, 4, 8, 8, 9, 3, 5, 7, 8, 6, 6, 5, 9, 9, 1, 4, 6, 8, 9, 7, 7, 6,
-                        1, 1, 2, 5, 7, 9, 1, 8, 8, 7, 2, 2, 3, 6, 8, 1, 2, 9, 9, 8, 3, 3, 4, 7, 9
-                    ],
-                ]
-            }
-        )
+            cave.render_route(&route),
+            ["1...", "1111", "...1"].join("\n")
+        );
     }
 
     #[test]
-    fn test_part1_ex1() {
-        let actual = part1(&parse(&ex1()));
-        assert_eq!(actual, 40);
+    fn test_explain() {
+        let cave = parse(&["1999", "1111", "9991"].join("\n")).unwrap();
+        let expected = ["1...", "1111", "...1", "total risk: 5"].join("\n");
+        assert_eq!(explain(&cave, "route"), Ok(expected));
+        let tiled = explain(&cave, "tiled-route").unwrap();
+        assert_eq!(tiled.lines().count(), 16);
+        let risks = [" 0  9 12 13", " 1  2  3  4", "10 11 12  5"].join("\n");
+        assert_eq!(explain(&cave, "risks"), Ok(risks));
+        assert!(explain(&cave, "maze").is_err());
     }
 
     #[test]
-    fn test_part1_real() {
-        let actual = part1(&parse(&real()));
-        assert_eq!(actual, 527);
+    fn test_find_route_diagonal() {
+        let cave = parse(&["1999", "9199", "9919", "9991"].join("\n")).unwrap();
+        let orthogonal = find_route(&cave, (0, 0), (3, 3), &Moves::default()).unwrap();
+        assert_eq!(orthogonal.cost, 30);
+        let moves = Moves {
+            neighborhood: Neighborhood::All,
+            ..Moves::default()
+        };
+        let diagonal = find_route(&cave, (0, 0), (3, 3), &moves).unwrap();
+        assert_eq!(diagonal.cost, 3);
+        assert_eq!(diagonal.states, vec![(0, 0), (1, 1), (2, 2), (3, 3)]);
+    }
+
+    #[test]
+    fn test_find_route_blocked() {
+        let cave = parse(&["1999", "1111", "9991"].join("\n")).unwrap();
+        let moves = Moves {
+            blocked: HashSet::from([(2, 1)]),
+            ..Moves::default()
+        };
+        let route = find_route(&cave, (3, 2), (0, 0), &moves).unwrap();
+        assert_eq!(route.cost, 21);
+        assert!(!route.states.contains(&(2, 1)));
+        let walled_in = Moves {
+            blocked: HashSet::from([(0, 1), (1, 0)]),
+            ..Moves::default()
+        };
+        assert_eq!(find_route(&cave, (3, 2), (0, 0), &walled_in), None);
+        assert_eq!(find_route(&cave, (4, 0), (0, 0), &moves), None);
     }
 
     #[test]
-    fn test_part2_ex1() {
-        let actual = part2(&parse(&ex1()));
-        assert_eq!(actual, 315);
+    fn test_risk_table() {
+        let cave = parse(&ex1()).unwrap();
+        let table = risk_table(&cave, (0, 0), &Moves::default()).unwrap();
+        assert_eq!(table.len(), 100);
+        assert_eq!(table.cost(&(0, 0)), Some(0));
+        assert_eq!(table.cost(&(0, 2)), Some(3));
+        assert_eq!(table.cost(&(9, 9)), Some(40));
+        assert_eq!(
+            table.path(&(0, 1)).map(|r| r.states),
+            Some(vec![(0, 0), (0, 1)])
+        );
+        assert!(risk_table(&cave, (10, 0), &Moves::default()).is_none());
+    }
+
+    #[test]
+    fn test_part1_real() {
+        let actual = part1(&parse(&real()).unwrap());
+        assert_eq!(actual, 527);
     }
 
     #[test]
     fn test_part2_real() {
-        let actual = part2(&parse(&real()));
+        let actual = part2(&parse(&real()).unwrap());
         assert_eq!(actual, 2887);
     }
 }
@@ -1,3 +1,6 @@
+use crate::error::ParseError;
+use crate::solution::Solution;
+
 pub trait Packet {
     fn get_version(&self) -> u8;
     fn get_type_id(&self) -> u8;
@@ -104,21 +107,23 @@ struct Bits {
 }
 
 impl Bits {
-    fn new(input: &str) -> Self {
+    fn new(input: &str) -> Result<Self, ParseError> {
         let mut data = vec![];
         data.reserve(input.len() * 4);
-        for ch in input.trim().chars() {
-            let v = ch.to_digit(16).expect("Invalid hex digit");
+        for (idx, ch) in input.trim().chars().enumerate() {
+            let v = ch
+                .to_digit(16)
+                .ok_or_else(|| ParseError::new(16, 1, idx + 1, "a hex digit"))?;
             for shift in (0..4).rev() {
                 data.push((v & (1 << shift)) != 0);
             }
         }
 
-        Self { data, ptr: 0 }
+        Ok(Self { data, ptr: 0 })
     }
 
     // Generic-ness from https://github.com/gokberkkocak/adventofcode/blob/master/src/aoc2021/day16.rs
-    fn read_bits<T>(&mut self, num: usize) -> T
+    fn read_bits<T>(&mut self, num: usize) -> Result<T, ParseError>
     where
         T: std::ops::Shl<Output = T> + std::ops::BitOr<Output = T> + From<u8> + Default,
     {
@@ -126,6 +131,10 @@ impl Bits {
         if num > 8 * std::mem::size_of::<T>() {
             panic!("TOO MANY BITS");
         }
+        if self.ptr + num > self.data.len() {
+            // point at the hex digit where we ran out
+            return Err(ParseError::new(16, 1, self.ptr / 4 + 1, "more packet data"));
+        }
         for i in self.ptr..(self.ptr + num) {
             if self.data[i] {
                 r = (r << T::from(1)) | T::from(1);
@@ -135,63 +144,63 @@ impl Bits {
         }
         self.ptr += num;
 
-        r
+        Ok(r)
     }
 
-    fn read_packet(&mut self) -> Box<dyn Packet> {
-        let version: u8 = self.read_bits(3);
-        let type_id: u8 = self.read_bits(3);
+    fn read_packet(&mut self) -> Result<Box<dyn Packet>, ParseError> {
+        let version: u8 = self.read_bits(3)?;
+        let type_id: u8 = self.read_bits(3)?;
 
         if type_id == 4 {
             let mut value = 0u64;
             let mut last_nibble = false;
 
             while !last_nibble {
-                last_nibble = self.read_bits::<u8>(1) == 0;
-                let nibble: u64 = self.read_bits(4);
+                last_nibble = self.read_bits::<u8>(1)? == 0;
+                let nibble: u64 = self.read_bits(4)?;
                 value = (value << 4) | nibble;
             }
 
-            return Box::new(PacketLiteral {
+            return Ok(Box::new(PacketLiteral {
                 version,
                 type_id,
                 value,
-            });
+            }));
         }
 
         // operator
-        let length_type_id: u8 = self.read_bits(1);
+        let length_type_id: u8 = self.read_bits(1)?;
         let mut sub_packets = vec![];
 
         if length_type_id == 0 {
             // 15 bits for total length of bits for subpackets
-            let num_bits: usize = self.read_bits(15);
+            let num_bits: usize = self.read_bits(15)?;
             let end_bits = self.ptr + num_bits;
 
             while self.ptr < end_bits {
-                let sub_packet = self.read_packet();
+                let sub_packet = self.read_packet()?;
                 sub_packets.push(sub_packet);
             }
         } else {
             // 11 bits for the number of subpackets
-            let num_packets = self.read_bits(11);
+            let num_packets = self.read_bits(11)?;
 
             for _ in 0..num_packets {
-                let sub_packet = self.read_packet();
+                let sub_packet = self.read_packet()?;
                 sub_packets.push(sub_packet);
             }
         }
 
-        Box::new(PacketOperator {
+        Ok(Box::new(PacketOperator {
             version,
             type_id,
             sub_packets,
-        })
+        }))
     }
 }
 
-pub fn parse(input: &str) -> Box<dyn Packet> {
-    Bits::new(input).read_packet()
+pub fn parse(input: &str) -> Result<Box<dyn Packet>, ParseError> {
+    Bits::new(input)?.read_packet()
 }
 
 pub fn part1(packet: &Box<dyn Packet>) -> u32 {
@@ -202,26 +211,33 @@ pub fn part2(packet: &Box<dyn Packet>) -> u64 {
     packet.get_value()
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use crate::util;
+pub struct Day16;
 
-    fn ex1() -> String {
-        "8A004A801A8002F478".to_string()
-    }
+impl Solution for Day16 {
+    type Input = Box<dyn Packet>;
+    type Output1 = u32;
+    type Output2 = u64;
+
+    const DAY: i32 = 16;
+    const TITLE: &'static str = "Packet Decoder";
 
-    fn ex2() -> String {
-        "620080001611562C8802118E34".to_string()
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        parse(input)
     }
 
-    fn ex3() -> String {
-        "C0015000016115A2E0802F182340".to_string()
+    fn part1(input: &Self::Input) -> Self::Output1 {
+        part1(input)
     }
 
-    fn ex4() -> String {
-        "A0016C880162017C3686B18A3D4780".to_string()
+    fn part2(input: &Self::Input) -> Self::Output2 {
+        part2(input)
     }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::util;
 
     fn real() -> String {
         util::read_input(16)
@@ -230,7 +246,7 @@ mod tests {
     #[test]
     fn test_parse() {
         let input = "0F1E";
-        let actual = Bits::new(input);
+        let actual = Bits::new(input).unwrap();
         assert_eq!(
             actual.data,
             vec![
@@ -243,76 +259,26 @@ mod tests {
     }
 
     #[test]
-    fn test_part1_ex1() {
-        let actual = part1(&parse(&ex1()));
-        assert_eq!(actual, 16);
-    }
-    #[test]
-    fn test_part1_ex2() {
-        let actual = part1(&parse(&ex2()));
-        assert_eq!(actual, 12);
-    }
-    #[test]
-    fn test_part1_ex3() {
-        let actual = part1(&parse(&ex3()));
-        assert_eq!(actual, 23);
-    }
-    #[test]
-    fn test_part1_ex4() {
-        let actual = part1(&parse(&ex4()));
-        assert_eq!(actual, 31);
+    fn test_parse_invalid() {
+        assert_eq!(
+            parse("D2FG28").err(),
+            Some(ParseError::new(16, 1, 4, "a hex digit"))
+        );
+        assert_eq!(
+            parse("D2FE").err(),
+            Some(ParseError::new(16, 1, 5, "more packet data"))
+        );
     }
 
     #[test]
     fn test_part1_real() {
-        let actual = part1(&parse(&real()));
+        let actual = part1(&parse(&real()).unwrap());
         assert_eq!(actual, 821);
     }
 
-    #[test]
-    fn test_part2_ex1() {
-        let actual = part2(&parse("C200B40A82"));
-        assert_eq!(actual, 3);
-    }
-    #[test]
-    fn test_part2_ex2() {
-        let actual = part2(&parse("04005AC33890"));
-        assert_eq!(actual, 54);
-    }
-    #[test]
-    fn test_part2_ex3() {
-        let actual = part2(&parse("880086C3E88112"));
-        assert_eq!(actual, 7);
-    }
-    #[test]
-    fn test_part2_ex4() {
-        let actual = part2(&parse("CE00C43D881120"));
-        assert_eq!(actual, 9);
-    }
-    #[test]
-    fn test_part2_ex5() {
-        let actual = part2(&parse("D8005AC2A8F0"));
-        assert_eq!(actual, 1);
-    }
-    #[test]
-    fn test_part2_ex6() {
-        let actual = part2(&parse("F600BC2D8F"));
-        assert_eq!(actual, 0);
-    }
-    #[test]
-    fn test_part2_ex7() {
-        let actual = part2(&parse("9C005AC2F8F0"));
-        assert_eq!(actual, 0);
-    }
-    #[test]
-    fn test_part2_ex8() {
-        let actual = part2(&parse("9C0141080250320F1802104A08"));
-        assert_eq!(actual, 1);
-    }
-
     #[test]
     fn test_part2_real() {
-        let actual = part2(&parse(&real()));
+        let actual = part2(&parse(&real()).unwrap());
         assert_eq!(actual, 2056021084691);
     }
 }
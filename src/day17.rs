@@ -1,3 +1,6 @@
+use crate::error::{input_lines, ParseError};
+use crate::solution::Solution;
+
 #[derive(Debug, Eq, PartialEq)]
 pub struct TargetArea {
     x_min: i32,
@@ -27,40 +30,27 @@ impl TargetArea {
     }
 }
 
-pub fn parse(input: &str) -> TargetArea {
+pub fn parse(input: &str) -> Result<TargetArea, ParseError> {
     let re = regex::Regex::new(
         // r"target area: x=(?P<min_x>\d+)..(?P<max_x>\d+), y(?P<min_y>-?\d+)..(?P<max_y>-?\d+)",
         r"target area: x=(?P<min_x>\d+)\.\.(?P<max_x>\d+), y=(?P<min_y>-\d+)\.\.(?P<max_y>-\d+)",
     )
     .expect("Invalid regex");
-    let caps = re.captures(input).expect("Invalid input");
-
-    TargetArea {
-        x_min: caps
-            .name("min_x")
-            .expect("Invalid input (min_x)")
-            .as_str()
-            .parse()
-            .expect("Invalid number (min_x)"),
-        x_max: caps
-            .name("max_x")
-            .expect("Invalid input (max_x)")
-            .as_str()
-            .parse()
-            .expect("Invalid number (max_x)"),
-        y_min: caps
-            .name("min_y")
-            .expect("Invalid input (min_y)")
-            .as_str()
-            .parse()
-            .expect("Invalid number (min_y)"),
-        y_max: caps
-            .name("max_y")
-            .expect("Invalid input (max_y)")
-            .as_str()
-            .parse()
-            .expect("Invalid number (max_y)"),
-    }
+    let line = input_lines(17, input)
+        .next()
+        .ok_or_else(|| ParseError::new(17, 1, 1, "a target area"))?;
+    let caps = re
+        .captures(line.text)
+        .ok_or_else(|| line.error("a target area like target area: x=20..30, y=-10..-5"))?;
+    // the regex only matches digits, so the only way for these to fail is overflow
+    let num = |name: &str| line.parse(&caps[name], "a number that fits in an i32");
+
+    Ok(TargetArea {
+        x_min: num("min_x")?,
+        x_max: num("max_x")?,
+        y_min: num("min_y")?,
+        y_max: num("max_y")?,
+    })
 }
 
 pub fn part1(target: &TargetArea) -> i32 {
@@ -112,13 +102,36 @@ pub fn part2(target: &TargetArea) -> i32 {
     num_velocities
 }
 
+pub struct Day17;
+
+impl Solution for Day17 {
+    type Input = TargetArea;
+    type Output1 = i32;
+    type Output2 = i32;
+
+    const DAY: i32 = 17;
+    const TITLE: &'static str = "Trick Shot";
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        parse(input)
+    }
+
+    fn part1(input: &Self::Input) -> Self::Output1 {
+        part1(input)
+    }
+
+    fn part2(input: &Self::Input) -> Self::Output2 {
+        part2(input)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::util;
 
     fn ex1() -> String {
-        "target area: x=20..30, y=-10..-5".to_string()
+        util::read_example(17, 1)
     }
 
     fn real() -> String {
@@ -127,7 +140,7 @@ mod tests {
 
     #[test]
     fn test_parse_ex1() {
-        let actual = parse(&ex1());
+        let actual = parse(&ex1()).unwrap();
         assert_eq!(
             actual,
             TargetArea {
@@ -139,26 +152,23 @@ mod tests {
         )
     }
     #[test]
-    fn test_part1_ex1() {
-        let actual = part1(&parse(&ex1()));
-        assert_eq!(actual, 45);
+    fn test_parse_invalid() {
+        let actual = parse("target area: x=20..30, y=-10..-99999999999");
+        assert_eq!(
+            actual,
+            Err(ParseError::new(17, 1, 31, "a number that fits in an i32"))
+        );
     }
 
     #[test]
     fn test_part1_real() {
-        let actual = part1(&parse(&real()));
+        let actual = part1(&parse(&real()).unwrap());
         assert_eq!(actual, 4278);
     }
 
-    #[test]
-    fn test_part2_ex1() {
-        let actual = part2(&parse(&ex1()));
-        assert_eq!(actual, 112);
-    }
-
     #[test]
     fn test_part2_real() {
-        let actual = part2(&parse(&real()));
+        let actual = part2(&parse(&real()).unwrap());
         assert_eq!(actual, 1994);
     }
 }
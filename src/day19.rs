@@ -1,7 +1,10 @@
+use crate::error::ParseError;
+use crate::solution::Solution;
+
 pub struct Puzzle {}
 
-pub fn parse(input: &str) -> Puzzle {
-    Puzzle {}
+pub fn parse(_input: &str) -> Result<Puzzle, ParseError> {
+    Ok(Puzzle {})
 }
 
 pub fn part1(_input: &Puzzle) -> i32 {
@@ -12,40 +15,47 @@ pub fn part2(_input: &Puzzle) -> i32 {
     0
 }
 
+pub struct Day19;
+
+impl Solution for Day19 {
+    type Input = Puzzle;
+    type Output1 = i32;
+    type Output2 = i32;
+
+    const DAY: i32 = 19;
+    const TITLE: &'static str = "Beacon Scanner";
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        parse(input)
+    }
+
+    fn part1(input: &Self::Input) -> Self::Output1 {
+        part1(input)
+    }
+
+    fn part2(input: &Self::Input) -> Self::Output2 {
+        part2(input)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::util;
 
-    fn ex1() -> String {
-        vec!["TODO"].join("\n")
-    }
-
     fn real() -> String {
         util::read_input(19)
     }
 
-    #[test]
-    fn test_part1_ex1() {
-        let actual = part1(&parse(&ex1()));
-        assert_eq!(actual, 0);
-    }
-
     #[test]
     fn test_part1_real() {
-        let actual = part1(&parse(&real()));
-        assert_eq!(actual, 0);
-    }
-
-    #[test]
-    fn test_part2_ex1() {
-        let actual = part2(&parse(&ex1()));
+        let actual = part1(&parse(&real()).unwrap());
         assert_eq!(actual, 0);
     }
 
     #[test]
     fn test_part2_real() {
-        let actual = part2(&parse(&real()));
+        let actual = part2(&parse(&real()).unwrap());
         assert_eq!(actual, 0);
     }
 }
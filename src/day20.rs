@@ -1,3 +1,6 @@
+use crate::error::{input_lines, InputLine, ParseError};
+use crate::solution::Solution;
+
 #[derive(Clone)]
 pub struct MapImage {
     image_enhancement: Vec<bool>,
@@ -97,39 +100,43 @@ impl ToString for MapImage {
     }
 }
 
-pub fn parse_pixel(ch: char) -> bool {
-    if ch == '#' {
-        true
-    } else if ch == '.' {
-        false
-    } else {
-        panic!("Invalid map character {}", ch);
+pub fn parse_pixel(ch: char) -> Option<bool> {
+    match ch {
+        '#' => Some(true),
+        '.' => Some(false),
+        _ => None,
     }
 }
 
-pub fn parse(input: &str) -> MapImage {
-    let mut lines = input.lines();
-    let image_enhancement = lines
+fn parse_pixels(line: InputLine<'_>) -> impl Iterator<Item = Result<bool, ParseError>> + '_ {
+    line.chars().map(move |(column, ch)| {
+        parse_pixel(ch).ok_or_else(|| ParseError::new(20, line.number, column, "'#' or '.'"))
+    })
+}
+
+pub fn parse(input: &str) -> Result<MapImage, ParseError> {
+    let mut lines = input_lines(20, input);
+    let enhancement_line = lines
         .next()
-        .expect("No image enhancement line")
-        .chars()
-        .map(parse_pixel)
-        .collect();
-    let blank = lines.next().expect("No blank");
-    if !blank.is_empty() {
-        panic!("Expected blank line");
+        .ok_or_else(|| ParseError::new(20, 1, 1, "an image enhancement algorithm"))?;
+    let image_enhancement = parse_pixels(enhancement_line).collect::<Result<_, _>>()?;
+    let blank = lines
+        .next()
+        .ok_or_else(|| ParseError::new(20, 2, 1, "a blank line"))?;
+    if !blank.text.is_empty() {
+        return Err(blank.error("a blank line"));
     }
     let mut image = MapImage::new(image_enhancement);
 
     for (y, line) in lines.enumerate() {
-        for (x, ch) in line.chars().enumerate() {
-            if parse_pixel(ch) {
+        for (x, pixel) in parse_pixels(line).enumerate() {
+            if pixel? {
                 image.set_pixel(x as i32, y as i32);
             }
         }
     }
 
-    image
+    Ok(image)
 }
 
 pub fn part1(image: &MapImage) -> usize {
@@ -152,48 +159,53 @@ pub fn part2(image: &MapImage) -> usize {
     image.image.len()
 }
 
+pub struct Day20;
+
+impl Solution for Day20 {
+    type Input = MapImage;
+    type Output1 = usize;
+    type Output2 = usize;
+
+    const DAY: i32 = 20;
+    const TITLE: &'static str = "Trench Map";
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        parse(input)
+    }
+
+    fn part1(input: &Self::Input) -> Self::Output1 {
+        part1(input)
+    }
+
+    fn part2(input: &Self::Input) -> Self::Output2 {
+        part2(input)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::util;
 
-    fn ex1() -> String {
-        vec![
-            "..#.#..#####.#.#.#.###.##.....###.##.#..###.####..#####..#....#..#..##..###..######.###...####..#..#####..##..#.#####...##.#.#..#.##..#.#......#.###.######.###.####...#.##.##..#..#..#####.....#.#....###..#.##......#.....#..#..#..##..#...##.######.####.####.#.#...#.......#..#.#.#...####.##.#......#..#...##.#.##..#...##.#.##..###.#......#.#.......#.#.#.####.###.##...#.....####.#..#..#.##.#....##..#.####....##...##..#...#......#.#.......#.......##..####..#...#.#.#...##..#.#..###..#####........#..####......#..#",
-            "",
-            "#..#.",
-            "#....",
-            "##..#",
-            "..#..",
-            "..###",            
-        ].join("\n")
-    }
-
     fn real() -> String {
         util::read_input(20)
     }
 
     #[test]
-    fn test_part1_ex1() {
-        let actual = part1(&parse(&ex1()));
-        assert_eq!(actual, 35);
+    fn test_parse_invalid() {
+        let actual = parse("#.#\n\n#.#\n.x.").err();
+        assert_eq!(actual, Some(ParseError::new(20, 4, 2, "'#' or '.'")));
     }
 
     #[test]
     fn test_part1_real() {
-        let actual = part1(&parse(&real()));
+        let actual = part1(&parse(&real()).unwrap());
         assert_eq!(actual, 4964);
     }
 
-    #[test]
-    fn test_part2_ex1() {
-        let actual = part2(&parse(&ex1()));
-        assert_eq!(actual, 3351);
-    }
-
     #[test]
     fn test_part2_real() {
-        let actual = part2(&parse(&real()));
+        let actual = part2(&parse(&real()).unwrap());
         assert_eq!(actual, 13202);
     }
 }
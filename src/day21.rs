@@ -1,35 +1,33 @@
+use crate::error::{input_lines, ParseError};
+use crate::solution::Solution;
+
 #[derive(Debug, Eq, PartialEq)]
 pub struct GameBoard {
     player1: u16,
     player2: u16,
 }
 
-pub fn parse(input: &str) -> GameBoard {
-    let mut lines = input.lines();
-    let p1 = lines.next().expect("Missing line 1");
-    let p2 = lines.next().expect("Missing line 2");
+pub fn parse(input: &str) -> Result<GameBoard, ParseError> {
+    let mut lines = input_lines(21, input);
 
     let re =
         regex::Regex::new(r"Player [12] starting position: (?P<pos>\d+)").expect("Invalid regex");
 
-    let player1: u16 = re
-        .captures(p1)
-        .expect("Invalid line 1")
-        .name("pos")
-        .expect("Line 1 missing pos")
-        .as_str()
-        .parse()
-        .expect("Invalid position 1");
-    let player2: u16 = re
-        .captures(p2)
-        .expect("Invalid line 2")
-        .name("pos")
-        .expect("Line 2 missing pos")
-        .as_str()
-        .parse()
-        .expect("Invalid position 2");
-
-    GameBoard { player1, player2 }
+    let mut starting_position = |n: usize| -> Result<u16, ParseError> {
+        let expected = format!("Player {} starting position: <position>", n);
+        let line = lines
+            .next()
+            .ok_or_else(|| ParseError::new(21, n, 1, &expected))?;
+        let caps = re
+            .captures(line.text)
+            .ok_or_else(|| line.error(&expected))?;
+        line.parse(&caps["pos"], "a starting position")
+    };
+
+    let player1 = starting_position(1)?;
+    let player2 = starting_position(2)?;
+
+    Ok(GameBoard { player1, player2 })
 }
 
 #[derive(Clone, Copy)]
@@ -154,17 +152,36 @@ pub fn part2(starting: &GameBoard) -> usize {
     p1.max(p2)
 }
 
+pub struct Day21;
+
+impl Solution for Day21 {
+    type Input = GameBoard;
+    type Output1 = u32;
+    type Output2 = usize;
+
+    const DAY: i32 = 21;
+    const TITLE: &'static str = "Dirac Dice";
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        parse(input)
+    }
+
+    fn part1(input: &Self::Input) -> Self::Output1 {
+        part1(input)
+    }
+
+    fn part2(input: &Self::Input) -> Self::Output2 {
+        part2(input)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::util;
 
     fn ex1() -> String {
-        vec![
-            "Player 1 starting position: 4",
-            "Player 2 starting position: 8",
-        ]
-        .join("\n")
+        util::read_example(21, 1)
     }
 
     fn real() -> String {
@@ -173,7 +190,7 @@ mod tests {
 
     #[test]
     fn test_parse() {
-        let actual = parse(&ex1());
+        let actual = parse(&ex1()).unwrap();
         assert_eq!(
             actual,
             GameBoard {
@@ -182,27 +199,16 @@ mod tests {
             }
         );
     }
-    #[test]
-    fn test_part1_ex1() {
-        let actual = part1(&parse(&ex1()));
-        assert_eq!(actual, 739785);
-    }
 
     #[test]
     fn test_part1_real() {
-        let actual = part1(&parse(&real()));
+        let actual = part1(&parse(&real()).unwrap());
         assert_eq!(actual, 1002474);
     }
 
-    #[test]
-    fn test_part2_ex1() {
-        let actual = part2(&parse(&ex1()));
-        assert_eq!(actual, 444356092776315);
-    }
-
     #[test]
     fn test_part2_real() {
-        let actual = part2(&parse(&real()));
+        let actual = part2(&parse(&real()).unwrap());
         assert_eq!(actual, 919758187195363);
     }
 }
@@ -1,7 +1,21 @@
+use crate::error::{input_lines, InputLine, ParseError};
+use crate::solution::Solution;
+use format::Language;
+use intern::Interner;
+use ranges::Ranges;
 use regex::Regex;
+use std::cell::RefCell;
 use std::collections::HashMap;
+use std::fmt;
+use std::ptr;
 use std::rc::Rc;
 
+mod compile;
+mod disassemble;
+mod format;
+mod intern;
+mod ranges;
+
 #[derive(Clone, Debug)]
 enum Expression {
     Constant(i64),
@@ -13,56 +27,159 @@ enum Expression {
     Eql(Rc<Expression>, Rc<Expression>),
 }
 
-impl ToString for Expression {
-    fn to_string(&self) -> String {
-        match self {
-            Expression::Constant(c) => c.to_string(),
-            Expression::Input(i) => format!("i{}", i),
-            Expression::Add(lhs, rhs) => format!("(+ {}\n   {})", lhs.to_string(), rhs.to_string()),
-            Expression::Mul(lhs, rhs) => format!("(* {}\n   {})", lhs.to_string(), rhs.to_string()),
-            Expression::Div(lhs, rhs) => format!("(/ {}\n   {})", lhs.to_string(), rhs.to_string()),
-            Expression::Mod(lhs, rhs) => format!("(% {}\n   {})", lhs.to_string(), rhs.to_string()),
-            Expression::Eql(lhs, rhs) => format!("(= {}\n   {})", lhs.to_string(), rhs.to_string()),
+/// Equal as expressions, however the nodes are shared. Operands that are the very same node are
+/// equal without looking inside them, which for interned nodes is all of them.
+impl PartialEq for Expression {
+    fn eq(&self, other: &Expression) -> bool {
+        if ptr::eq(self, other) {
+            return true;
+        }
+        match (self, other) {
+            (Expression::Constant(a), Expression::Constant(b)) => a == b,
+            (Expression::Input(a), Expression::Input(b)) => a == b,
+            _ => match (self.operands(), other.operands()) {
+                (Some((op, a, b)), Some((other_op, c, d))) => {
+                    op == other_op && (Rc::ptr_eq(a, c) || a == c) && (Rc::ptr_eq(b, d) || b == d)
+                }
+                _ => false,
+            },
+        }
+    }
+}
+
+impl Eq for Expression {}
+
+impl Expression {
+    /// Whether this is the same kind of node as `other` on the very same operand nodes. That's
+    /// O(1), and for interned nodes the same as being equal.
+    pub(super) fn same_node(&self, other: &Expression) -> bool {
+        match (self, other) {
+            (Expression::Constant(a), Expression::Constant(b)) => a == b,
+            (Expression::Input(a), Expression::Input(b)) => a == b,
+            _ => match (self.operands(), other.operands()) {
+                (Some((op, a, b)), Some((other_op, c, d))) => {
+                    op == other_op && Rc::ptr_eq(a, c) && Rc::ptr_eq(b, d)
+                }
+                _ => false,
+            },
         }
     }
 }
 
+/// The smallest and largest values an input digit can have.
+const DIGITS: (i64, i64) = (1, 9);
+
+/// The most paths `ALU::explore` follows. MONAD forks at most once at each of its seven pops,
+/// into no more than 128.
+const PATH_LIMIT: usize = 1 << 10;
+
+fn constant(c: i64) -> Rc<Expression> {
+    Rc::new(Expression::Constant(c))
+}
+
 impl Expression {
-    fn print(&self) {
+    fn as_constant(&self) -> Option<i64> {
         match self {
-            Expression::Constant(c) => print!("{}", c),
-            Expression::Input(i) => print!("i{}", i),
-            Expression::Add(lhs, rhs) => {
-                print!("(+ ");
-                lhs.print();
-                print!("\n   ");
-                rhs.print();
+            Expression::Constant(c) => Some(*c),
+            _ => None,
+        }
+    }
+
+    /// If this is `a * d` or `a * d + r` with `a` not negative and `r` in `0..d`, returns `a` and
+    /// `r`. That's how MONAD pushes `r` onto the base 26 stack in `z`.
+    fn split_multiple(
+        &self,
+        ranges: &mut Ranges,
+        d: i64,
+    ) -> Option<(Rc<Expression>, Option<Rc<Expression>>)> {
+        let mut multiple = |e: &Expression| match e {
+            Expression::Mul(a, b) if b.as_constant() == Some(d) && ranges.of(a).0 >= 0 => {
+                Some(a.clone())
             }
-            Expression::Mul(lhs, rhs) => {
-                print!("(* ");
-                lhs.print();
-                print!("\n   ");
-                rhs.print();
-                println!(")");
+            Expression::Mul(b, a) if b.as_constant() == Some(d) && ranges.of(a).0 >= 0 => {
+                Some(a.clone())
             }
-            Expression::Div(lhs, rhs) => {
-                print!("(/ ");
-                lhs.print();
-                print!("\n   ");
-                rhs.print();
+            _ => None,
+        };
+        match self {
+            Expression::Mul(..) => multiple(self).map(|a| (a, None)),
+            Expression::Add(lhs, rhs) => {
+                let a = multiple(lhs)?;
+                let (low, high) = ranges.of(rhs);
+                if low < 0 || high >= d {
+                    return None;
+                }
+                Some((a, Some(rhs.clone())))
             }
-            Expression::Mod(lhs, rhs) => {
-                print!("(% ");
-                lhs.print();
-                print!("\n   ");
-                rhs.print();
+            _ => None,
+        }
+    }
+
+    fn add(lhs: Rc<Expression>, rhs: Rc<Expression>) -> Rc<Expression> {
+        match (lhs.as_constant(), rhs.as_constant()) {
+            (Some(a), Some(b)) if a.checked_add(b).is_some() => constant(a + b),
+            (Some(0), _) => rhs,
+            (_, Some(0)) => lhs,
+            _ => Rc::new(Expression::Add(lhs, rhs)),
+        }
+    }
+
+    fn mul(lhs: Rc<Expression>, rhs: Rc<Expression>) -> Rc<Expression> {
+        match (lhs.as_constant(), rhs.as_constant()) {
+            (Some(a), Some(b)) if a.checked_mul(b).is_some() => constant(a * b),
+            (Some(0), _) | (_, Some(0)) => constant(0),
+            (Some(1), _) => rhs,
+            (_, Some(1)) => lhs,
+            _ => Rc::new(Expression::Mul(lhs, rhs)),
+        }
+    }
+
+    fn div(ranges: &mut Ranges, lhs: Rc<Expression>, rhs: Rc<Expression>) -> Rc<Expression> {
+        match (lhs.as_constant(), rhs.as_constant()) {
+            (Some(a), Some(b)) if a.checked_div(b).is_some() => constant(a / b),
+            (_, Some(1)) => lhs,
+            (_, Some(d)) if d > 0 => {
+                let (low, high) = ranges.of(&lhs);
+                if low >= 0 && high < d {
+                    constant(0)
+                } else if let Some((a, _)) = lhs.split_multiple(ranges, d) {
+                    a
+                } else {
+                    Rc::new(Expression::Div(lhs, rhs))
+                }
             }
-            Expression::Eql(lhs, rhs) => {
-                print!("(= ");
-                lhs.print();
-                print!("\n   ");
-                rhs.print();
+            _ => Rc::new(Expression::Div(lhs, rhs)),
+        }
+    }
+
+    fn rem(ranges: &mut Ranges, lhs: Rc<Expression>, rhs: Rc<Expression>) -> Rc<Expression> {
+        match (lhs.as_constant(), rhs.as_constant()) {
+            (Some(a), Some(b)) if a.checked_rem(b).is_some() => constant(a % b),
+            (_, Some(1)) => constant(0),
+            (_, Some(d)) if d > 0 => {
+                let (low, high) = ranges.of(&lhs);
+                if low >= 0 && high < d {
+                    lhs
+                } else if let Some((_, r)) = lhs.split_multiple(ranges, d) {
+                    r.unwrap_or_else(|| constant(0))
+                } else {
+                    Rc::new(Expression::Mod(lhs, rhs))
+                }
             }
+            _ => Rc::new(Expression::Mod(lhs, rhs)),
+        }
+    }
+
+    /// Folds to 1 when both sides are the same node, which for interned nodes is the same as
+    /// being equal.
+    fn eql(ranges: &mut Ranges, lhs: Rc<Expression>, rhs: Rc<Expression>) -> Rc<Expression> {
+        if lhs.same_node(&rhs) {
+            return constant(1);
+        }
+        let e = Expression::Eql(lhs, rhs);
+        match ranges.of_new(&e) {
+            (low, high) if low == high => constant(low),
+            _ => Rc::new(e),
         }
     }
 }
@@ -84,11 +201,13 @@ enum Operation {
 }
 
 impl Operation {
-    fn parse(loc: &str) -> Operation {
+    fn parse(line: InputLine) -> Result<Operation, ParseError> {
         let re =
             // Regex::new(r"^(<?op>[a-z]+)").expect("Invalid regex");
-            Regex::new(r"^(?P<op>[a-z]+) (?P<a>[a-z])( (?P<b>[a-z]|-?\d+))?$").expect("Invalid regex");
-        let cap = re.captures(loc).expect("Invalid line of code");
+            Regex::new(r"^(?P<op>[a-z]+) (?P<a>[wxyz])( (?P<b>[wxyz]|-?\d+))?$").expect("Invalid regex");
+        let cap = re
+            .captures(line.text)
+            .ok_or_else(|| line.error("an instruction like add x 12"))?;
 
         let op = cap.name("op").expect("Missing op").as_str();
         let a = cap
@@ -100,233 +219,499 @@ impl Operation {
             .expect("Invalid a");
 
         if op == "inp" {
-            return Operation::Inp(a);
+            return Ok(Operation::Inp(a));
         }
 
-        let b = cap.name("b").expect("Missing b").as_str();
-        let b = match b.parse::<i64>() {
-            Ok(v) => Value::Literal(v),
-            _ => Value::Variable(b.chars().next().expect("Invalid b")),
+        let b = cap
+            .name("b")
+            .ok_or_else(|| line.error_at_end("a register or number"))?
+            .as_str();
+        let b = match b.chars().next() {
+            Some(ch) if ch.is_ascii_lowercase() => Value::Variable(ch),
+            _ => Value::Literal(line.parse(b, "a number that fits in an i64")?),
         };
 
         match op {
-            "add" => Operation::Add(a, b),
-            "mul" => Operation::Mul(a, b),
-            "div" => Operation::Div(a, b),
-            "mod" => Operation::Mod(a, b),
-            "eql" => Operation::Eql(a, b),
-            _ => panic!("Invalid op"),
+            "add" => Ok(Operation::Add(a, b)),
+            "mul" => Ok(Operation::Mul(a, b)),
+            "div" => Ok(Operation::Div(a, b)),
+            "mod" => Ok(Operation::Mod(a, b)),
+            "eql" => Ok(Operation::Eql(a, b)),
+            _ => Err(line.error("one of inp, add, mul, div, mod or eql")),
         }
     }
 
     fn exec(&self, alu: &mut ALU) {
-        println!("({}) {:?}", alu.next_input, self);
-        match self {
+        let (ch, rhs) = match self {
             Operation::Inp(ch) => {
-                let exp = Expression::Input(alu.next_input);
+                let exp = Rc::new(Expression::Input(alu.next_input));
+                let exp = alu.interner.borrow_mut().intern(exp);
                 alu.next_input += 1;
                 alu.set(*ch, exp);
+                return;
             }
-            Operation::Add(ch, v) => {
-                // replace what's on the left hand side to make the borrow checker happy
-                let lhs = std::mem::replace(
-                    alu.memory
-                        .entry(*ch)
-                        .or_insert_with(|| Expression::Constant(0)),
-                    Expression::Constant(0),
-                );
-                let rhs = match v {
-                    Value::Literal(i) => alu
-                        .constants
-                        .entry(*i)
-                        .or_insert_with(|| Expression::Constant(*i)),
-                    Value::Variable(ch) => alu
-                        .memory
-                        .entry(*ch)
-                        .or_insert_with(|| Expression::Constant(0)),
-                };
-
-                let exp = match (lhs, rhs) {
-                    (Expression::Constant(lhs), Expression::Constant(rhs)) => {
-                        Expression::Constant(lhs + *rhs)
-                    }
-                    (lhs, rhs) => Expression::Add(Rc::new(lhs), Rc::new(rhs.clone())),
-                };
-                alu.set(*ch, exp);
-            }
-            Operation::Mul(ch, v) => {
-                // replace what's on the left hand side to make the borrow checker happy
-                let lhs = std::mem::replace(
-                    alu.memory
-                        .entry(*ch)
-                        .or_insert_with(|| Expression::Constant(0)),
-                    Expression::Constant(0),
-                );
-                let rhs = match v {
-                    Value::Literal(i) => alu
-                        .constants
-                        .entry(*i)
-                        .or_insert_with(|| Expression::Constant(*i)),
-                    Value::Variable(ch) => alu
-                        .memory
-                        .entry(*ch)
-                        .or_insert_with(|| Expression::Constant(0)),
-                };
-
-                let exp = match (lhs, rhs) {
-                    (Expression::Constant(lhs), Expression::Constant(rhs)) => {
-                        Expression::Constant(lhs * *rhs)
-                    }
-                    (lhs, rhs) => Expression::Mul(Rc::new(lhs), Rc::new(rhs.clone())),
-                };
-                alu.set(*ch, exp);
-            }
-            Operation::Div(ch, v) => {
-                // replace what's on the left hand side to make the borrow checker happy
-                let lhs = std::mem::replace(
-                    alu.memory
-                        .entry(*ch)
-                        .or_insert_with(|| Expression::Constant(0)),
-                    Expression::Constant(0),
-                );
-                let rhs = match v {
-                    Value::Literal(i) => alu
-                        .constants
-                        .entry(*i)
-                        .or_insert_with(|| Expression::Constant(*i)),
-                    Value::Variable(ch) => alu
-                        .memory
-                        .entry(*ch)
-                        .or_insert_with(|| Expression::Constant(0)),
-                };
-
-                let exp = match (lhs, rhs) {
-                    (Expression::Constant(lhs), Expression::Constant(rhs)) => {
-                        Expression::Constant(lhs / *rhs)
-                    }
-                    (lhs, rhs) => Expression::Div(Rc::new(lhs), Rc::new(rhs.clone())),
-                };
-                alu.set(*ch, exp);
+            Operation::Add(ch, v)
+            | Operation::Mul(ch, v)
+            | Operation::Div(ch, v)
+            | Operation::Mod(ch, v)
+            | Operation::Eql(ch, v) => (*ch, alu.value(v)),
+        };
+        let lhs = alu.get(ch);
+        let mut ranges = alu.ranges.borrow_mut();
+        let exp = match self {
+            Operation::Add(..) => Expression::add(lhs, rhs),
+            Operation::Mul(..) => Expression::mul(lhs, rhs),
+            Operation::Div(..) => Expression::div(&mut ranges, lhs, rhs),
+            Operation::Mod(..) => Expression::rem(&mut ranges, lhs, rhs),
+            _ => Expression::eql(&mut ranges, lhs, rhs),
+        };
+        drop(ranges);
+        let exp = alu.interner.borrow_mut().intern(exp);
+        alu.set(ch, exp);
+    }
+}
+
+impl fmt::Display for Value {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Value::Literal(i) => write!(f, "{}", i),
+            Value::Variable(ch) => write!(f, "{}", ch),
+        }
+    }
+}
+
+impl fmt::Display for Operation {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Operation::Inp(ch) => write!(f, "inp {}", ch),
+            Operation::Add(ch, v) => write!(f, "add {} {}", ch, v),
+            Operation::Mul(ch, v) => write!(f, "mul {} {}", ch, v),
+            Operation::Div(ch, v) => write!(f, "div {} {}", ch, v),
+            Operation::Mod(ch, v) => write!(f, "mod {} {}", ch, v),
+            Operation::Eql(ch, v) => write!(f, "eql {} {}", ch, v),
+        }
+    }
+}
+
+/// The registers of an ALU running on actual numbers.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct Registers {
+    pub w: i64,
+    pub x: i64,
+    pub y: i64,
+    pub z: i64,
+}
+
+impl Registers {
+    fn get(&self, ch: char) -> i64 {
+        match ch {
+            'w' => self.w,
+            'x' => self.x,
+            'y' => self.y,
+            _ => self.z,
+        }
+    }
+
+    fn get_mut(&mut self, ch: char) -> &mut i64 {
+        match ch {
+            'w' => &mut self.w,
+            'x' => &mut self.x,
+            'y' => &mut self.y,
+            _ => &mut self.z,
+        }
+    }
+
+    fn value(&self, v: &Value) -> i64 {
+        match v {
+            Value::Literal(i) => *i,
+            Value::Variable(ch) => self.get(*ch),
+        }
+    }
+}
+
+/// How many instructions [`ALU::execute`] runs before giving up, unless told otherwise.
+pub const STEP_LIMIT: usize = 100_000;
+
+/// Why a program couldn't run to the end. `step` counts instructions from 1, like line numbers.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum AluError {
+    DivisionByZero {
+        step: usize,
+    },
+    /// `mod a b` with `a` < 0 or `b` <= 0.
+    InvalidModulo {
+        step: usize,
+        a: i64,
+        b: i64,
+    },
+    Overflow {
+        step: usize,
+    },
+    OutOfInput {
+        step: usize,
+    },
+    StepLimit {
+        limit: usize,
+    },
+}
+
+impl fmt::Display for AluError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            AluError::DivisionByZero { step } => write!(f, "division by zero at step {}", step),
+            AluError::InvalidModulo { step, a, b } => {
+                write!(f, "invalid mod {} {} at step {}", a, b, step)
             }
-            Operation::Mod(ch, v) => {
-                // replace what's on the left hand side to make the borrow checker happy
-                let lhs = std::mem::replace(
-                    alu.memory
-                        .entry(*ch)
-                        .or_insert_with(|| Expression::Constant(0)),
-                    Expression::Constant(0),
-                );
-                let rhs = match v {
-                    Value::Literal(i) => alu
-                        .constants
-                        .entry(*i)
-                        .or_insert_with(|| Expression::Constant(*i)),
-                    Value::Variable(ch) => alu
-                        .memory
-                        .entry(*ch)
-                        .or_insert_with(|| Expression::Constant(0)),
-                };
-
-                let exp = match (lhs, rhs) {
-                    (Expression::Constant(lhs), Expression::Constant(rhs)) => {
-                        Expression::Constant(lhs % *rhs)
-                    }
-                    (lhs, rhs) => Expression::Mod(Rc::new(lhs), Rc::new(rhs.clone())),
-                };
-                alu.set(*ch, exp);
+            AluError::Overflow { step } => write!(f, "overflow at step {}", step),
+            AluError::OutOfInput { step } => write!(f, "ran out of input at step {}", step),
+            AluError::StepLimit { limit } => write!(f, "gave up after {} steps", limit),
+        }
+    }
+}
+
+impl Operation {
+    /// Runs this on actual numbers. `step` is only used for errors.
+    fn apply(
+        &self,
+        registers: &mut Registers,
+        inputs: &mut dyn Iterator<Item = i64>,
+        step: usize,
+    ) -> Result<(), AluError> {
+        let (ch, a, b) = match self {
+            Operation::Inp(ch) => {
+                *registers.get_mut(*ch) = inputs.next().ok_or(AluError::OutOfInput { step })?;
+                return Ok(());
             }
-            Operation::Eql(ch, v) => {
-                // replace what's on the left hand side to make the borrow checker happy
-                let lhs = std::mem::replace(
-                    alu.memory
-                        .entry(*ch)
-                        .or_insert_with(|| Expression::Constant(0)),
-                    Expression::Constant(0),
-                );
-                let rhs = match v {
-                    Value::Literal(i) => alu
-                        .constants
-                        .entry(*i)
-                        .or_insert_with(|| Expression::Constant(*i)),
-                    Value::Variable(ch) => alu
-                        .memory
-                        .entry(*ch)
-                        .or_insert_with(|| Expression::Constant(0)),
-                };
-
-                let exp = match (lhs, rhs) {
-                    (Expression::Constant(lhs), Expression::Constant(rhs)) => {
-                        Expression::Constant(if lhs == *rhs { 1 } else { 0 })
-                    }
-                    (Expression::Input(inp), Expression::Constant(rhs)) => {
-                        if *rhs < 1 || *rhs > 9 {
-                            Expression::Constant(0)
-                        } else {
-                            Expression::Eql(
-                                Rc::new(Expression::Input(inp)),
-                                Rc::new(Expression::Constant(*rhs)),
-                            )
-                        }
-                    }
-                    (lhs, rhs) => Expression::Eql(Rc::new(lhs), Rc::new(rhs.clone())),
-                };
-                alu.set(*ch, exp);
+            Operation::Add(ch, v)
+            | Operation::Mul(ch, v)
+            | Operation::Div(ch, v)
+            | Operation::Mod(ch, v)
+            | Operation::Eql(ch, v) => (*ch, registers.get(*ch), registers.value(v)),
+        };
+        let result = match self {
+            Operation::Add(..) => a.checked_add(b).ok_or(AluError::Overflow { step })?,
+            Operation::Mul(..) => a.checked_mul(b).ok_or(AluError::Overflow { step })?,
+            Operation::Div(..) if b == 0 => return Err(AluError::DivisionByZero { step }),
+            Operation::Div(..) => a.checked_div(b).ok_or(AluError::Overflow { step })?,
+            Operation::Mod(..) if a < 0 || b <= 0 => {
+                return Err(AluError::InvalidModulo { step, a, b })
             }
-        }
+            Operation::Mod(..) => a % b,
+            _ => (a == b) as i64,
+        };
+        *registers.get_mut(ch) = result;
+        Ok(())
     }
 }
 
+/// An `Eql` expression that is known to be 1 (`holds`) or 0 on one path through the program.
+#[derive(Clone, Debug)]
+struct Constraint {
+    condition: Rc<Expression>,
+    holds: bool,
+}
+
 #[derive(Clone, Debug)]
 pub struct ALU {
-    program: Vec<Operation>,
-    memory: HashMap<char, Expression>,
+    /// The whole program, which the symbolic run steps through with `pc` rather than using up.
+    program: Rc<[Operation]>,
+    pc: usize,
+    memory: HashMap<char, Rc<Expression>>,
     next_input: u8,
 
-    constants: HashMap<i64, Expression>,
+    /// Shared by every path forked from the same program.
+    interner: Rc<RefCell<Interner>>,
+    ranges: Rc<RefCell<Ranges>>,
+    constraints: Vec<Constraint>,
 }
 
 impl ALU {
+    /// Runs the whole program on one path, leaving any `eql` that can't be decided in the
+    /// expressions.
     fn run(&mut self) {
-        while let Some(op) = self.program.pop() {
+        while let Some(op) = self.next_operation() {
             op.exec(self);
         }
     }
 
-    fn get(&mut self, ch: char) -> &Expression {
-        self.memory
-            .entry(ch)
-            .or_insert_with(|| Expression::Constant(0))
+    fn next_operation(&mut self) -> Option<Operation> {
+        let op = self.program.get(self.pc)?.clone();
+        self.pc += 1;
+        Some(op)
+    }
+
+    /// Runs every path through the program. Whenever an `eql` can't be decided, the path forks
+    /// into one where it's 1 and one where it's 0, each remembering which it assumed. Gives up
+    /// with `None` once there are more than `max_paths` paths, which is when the program isn't
+    /// as simple as MONAD and would fork its way to exponentially many.
+    fn explore(self, max_paths: usize) -> Option<Vec<ALU>> {
+        let mut finished = vec![];
+        let mut pending = vec![self];
+        let mut paths = 1;
+
+        while let Some(mut alu) = pending.pop() {
+            let op = match alu.next_operation() {
+                Some(op) => op,
+                None => {
+                    finished.push(alu);
+                    continue;
+                }
+            };
+            op.exec(&mut alu);
+            if let Operation::Eql(ch, _) = op {
+                let condition = alu.get(ch);
+                if let Expression::Eql(..) = *condition {
+                    paths += 1;
+                    if paths > max_paths {
+                        return None;
+                    }
+                    let mut other = alu.clone();
+                    other.assume(ch, condition.clone(), false);
+                    alu.assume(ch, condition, true);
+                    pending.push(other);
+                }
+            }
+            pending.push(alu);
+        }
+
+        Some(finished)
+    }
+
+    fn assume(&mut self, ch: char, condition: Rc<Expression>, holds: bool) {
+        let value = self.interner.borrow_mut().constant(holds as i64);
+        self.set(ch, value);
+        self.constraints.push(Constraint { condition, holds });
+    }
+
+    /// The digits of the input, first to last, that satisfy this path's constraints and leave 0 in
+    /// `z`. Each digit is the first one in `digits` that still allows a solution.
+    fn solve(&self, digits: &[i64]) -> Option<Vec<i64>> {
+        let mut goals: Vec<(Rc<Expression>, i64)> = self
+            .constraints
+            .iter()
+            .map(|c| (c.condition.clone(), c.holds as i64))
+            .collect();
+        goals.push((self.get('z'), 0));
+
+        let feasible = |inputs: &[Option<i64>]| {
+            // the goals share most of their nodes
+            let mut ranges = Ranges::new(inputs);
+            goals.iter().all(|(e, want)| {
+                let (low, high) = ranges.of(e);
+                low <= *want && *want <= high
+            })
+        };
+
+        fn search<F: Fn(&[Option<i64>]) -> bool>(
+            inputs: &mut Vec<Option<i64>>,
+            next: usize,
+            digits: &[i64],
+            feasible: &F,
+        ) -> bool {
+            if next == inputs.len() {
+                return true;
+            }
+            for &d in digits {
+                inputs[next] = Some(d);
+                if feasible(inputs) && search(inputs, next + 1, digits, feasible) {
+                    return true;
+                }
+            }
+            inputs[next] = None;
+            false
+        }
+
+        let mut inputs = vec![None; self.next_input as usize];
+        if !feasible(&inputs) || !search(&mut inputs, 0, digits, &feasible) {
+            return None;
+        }
+        inputs.into_iter().collect()
+    }
+
+    /// Runs the program on actual numbers, reading `inp` values from `inputs`. Stops with an error
+    /// after `step_limit` instructions.
+    pub fn execute<I: IntoIterator<Item = i64>>(
+        &self,
+        inputs: I,
+        step_limit: usize,
+    ) -> Result<Registers, AluError> {
+        let mut registers = Registers::default();
+        let mut inputs = inputs.into_iter();
+        for (i, op) in self.program.iter().enumerate() {
+            if i == step_limit {
+                return Err(AluError::StepLimit { limit: step_limit });
+            }
+            op.apply(&mut registers, &mut inputs, i + 1)?;
+        }
+        Ok(registers)
+    }
+
+    /// The smallest and largest values `ch` can have with any input digits.
+    fn range(&self, ch: char) -> (i64, i64) {
+        self.ranges.borrow_mut().of(&self.get(ch))
+    }
+
+    fn get(&self, ch: char) -> Rc<Expression> {
+        self.memory.get(&ch).cloned().unwrap_or_else(|| constant(0))
     }
-    fn set(&mut self, ch: char, exp: Expression) {
+
+    fn set(&mut self, ch: char, exp: Rc<Expression>) {
         self.memory.insert(ch, exp);
     }
+
+    fn value(&mut self, v: &Value) -> Rc<Expression> {
+        match v {
+            Value::Literal(i) => self.interner.borrow_mut().constant(*i),
+            Value::Variable(ch) => self.get(*ch),
+        }
+    }
 }
 
-pub fn parse(input: &str) -> ALU {
-    return ALU {
-        program: input.lines().map(Operation::parse).rev().collect(),
-        memory: HashMap::new(),
+pub fn parse(input: &str) -> Result<ALU, ParseError> {
+    let program = input_lines(24, input)
+        .map(Operation::parse)
+        .collect::<Result<Rc<[_]>, _>>()?;
+
+    let mut interner = Interner::default();
+    let zero = interner.constant(0);
+    Ok(ALU {
+        program,
+        pc: 0,
+        memory: "wxyz".chars().map(|ch| (ch, zero.clone())).collect(),
         next_input: 0,
 
-        constants: HashMap::new(),
-    };
+        interner: Rc::new(RefCell::new(interner)),
+        ranges: Rc::new(RefCell::new(Ranges::default())),
+        constraints: vec![],
+    })
+}
+
+fn model_number(digits: &[i64]) -> i64 {
+    digits.iter().fold(0, |acc, d| acc * 10 + d)
+}
+
+/// The model numbers accepted by MONAD on every path through it, trying `digits` in order for each
+/// position, or `None` if there are too many paths to solve them one by one.
+fn model_numbers<'a>(init: &'a ALU, digits: &'a [i64]) -> Option<impl Iterator<Item = i64> + 'a> {
+    let compiled = init.compile();
+    let paths = init.clone().explore(PATH_LIMIT)?;
+    let numbers = paths
+        .into_iter()
+        // paths that can't end with z == 0 don't need solving
+        .filter(|path| {
+            let (low, high) = path.range('z');
+            low <= 0 && high >= 0
+        })
+        .filter_map(move |path| path.solve(digits))
+        .map(|digits| model_number(&digits))
+        // a last check against the real thing, in case a simplification was wrong
+        .filter(move |&n| compiled.accepts(n) == Ok(true));
+    Some(numbers)
 }
 
+/// The first model number found by running MONAD block by block, for programs that don't simplify
+/// well enough to be solved.
+fn search_model_number(init: &ALU, digits: &[i64]) -> Option<i64> {
+    init.compile()
+        .search(digits)
+        .map(|digits| model_number(&digits))
+}
+
+/// The largest model number accepted by MONAD, or -1 if there isn't one.
 pub fn part1(init: &ALU) -> i64 {
-    let mut meta = init.clone();
-    meta.run();
+    let digits = [9, 8, 7, 6, 5, 4, 3, 2, 1];
+    model_numbers(init, &digits)
+        .and_then(|numbers| numbers.max())
+        .or_else(|| search_model_number(init, &digits))
+        .unwrap_or(-1)
+}
 
-    meta.get('z').print();
+/// The smallest model number accepted by MONAD, or -1 if there isn't one.
+pub fn part2(init: &ALU) -> i64 {
+    let digits = [1, 2, 3, 4, 5, 6, 7, 8, 9];
+    model_numbers(init, &digits)
+        .and_then(|numbers| numbers.min())
+        .or_else(|| search_model_number(init, &digits))
+        .unwrap_or(-1)
+}
 
-    -1
+/// The model numbers read straight off the pushes and pops of MONAD's blocks.
+fn explain_blocks(init: &ALU) -> Result<String, String> {
+    let analysis =
+        disassemble::analyze(init).map_err(|err| format!("Not a MONAD program: {}", err))?;
+    let extreme = |digits: &[i64]| {
+        analysis
+            .model_number(digits)
+            .map_or("none".to_string(), |n| model_number(&n).to_string())
+    };
+    Ok(format!(
+        "{}\nlargest: {}\nsmallest: {}",
+        analysis,
+        extreme(&[9, 8, 7, 6, 5, 4, 3, 2, 1]),
+        extreme(&[1, 2, 3, 4, 5, 6, 7, 8, 9])
+    ))
 }
 
-fn input_to_i64(input: &Vec<i64>) -> i64 {
-    input.iter().rev().fold(0, |acc, d| acc * 10 + d)
+/// The views of a program for `--explain`: its MONAD `blocks`, what their digits have to satisfy
+/// and the model numbers that follow, or the simplified expression for z as `infix` algebra, a
+/// `pretty` s-expression, a `dot` graph or `rust` or `c` source. `counts` tells how many nodes
+/// z would take as a tree against how many it's made of once shared, and how many ranges the
+/// simplifiers needed.
+pub fn explain(init: &ALU, what: &str) -> Result<String, String> {
+    let run = || {
+        let mut alu = init.clone();
+        alu.run();
+        alu
+    };
+    let z = || run().get('z');
+    match what {
+        "blocks" => explain_blocks(init),
+        "counts" => {
+            let alu = run();
+            let counts = alu.interner.borrow().counts(&alu.get('z'));
+            let visits = alu.ranges.borrow().visits();
+            Ok(format!(
+                "as a tree: {}\ndistinct: {}\ninterned: {}\nranges worked out: {}",
+                counts.tree, counts.distinct, counts.interned, visits
+            ))
+        }
+        "infix" => Ok(z().to_string()),
+        "pretty" => Ok(z().pretty()),
+        "dot" => Ok(Expression::dot(&z())),
+        "rust" => Ok(Expression::source(&z(), Language::Rust)),
+        "c" => Ok(Expression::source(&z(), Language::C)),
+        _ => Err(format!(
+            "Unknown view '{}' of day 24, expected blocks, counts, infix, pretty, dot, rust or c",
+            what
+        )),
+    }
 }
 
-pub fn part2(_input: &ALU) -> i64 {
-    0
+pub struct Day24;
+
+impl Solution for Day24 {
+    type Input = ALU;
+    type Output1 = i64;
+    type Output2 = i64;
+
+    const DAY: i32 = 24;
+    const TITLE: &'static str = "Arithmetic Logic Unit";
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        parse(input)
+    }
+
+    fn part1(input: &Self::Input) -> Self::Output1 {
+        part1(input)
+    }
+
+    fn part2(input: &Self::Input) -> Self::Output2 {
+        part2(input)
+    }
+
+    fn explain(input: &Self::Input, what: &str) -> Result<String, String> {
+        explain(input, what)
+    }
 }
 
 #[cfg(test)]
@@ -340,12 +725,12 @@ mod tests {
 
     #[test]
     fn test_ex1() {
-        let mut alu = parse(&ex1());
+        let mut alu = parse(&ex1()).unwrap();
         alu.run();
         assert_eq!(alu.get('x').to_string(), "(i0) * (-1)");
     }
 
-    fn ex2() -> String {
+    pub(super) fn ex2() -> String {
         vec![
             "inp w", "add z w", "mod z 2", "div w 2", "add y w", "mod y 2", "div w 2", "add x w",
             "mod x 2", "div w 2", "mod w 2",
@@ -355,35 +740,336 @@ mod tests {
 
     #[test]
     fn test_ex2() {
-        let mut alu = parse(&ex2());
+        let mut alu = parse(&ex2()).unwrap();
         alu.run();
-        assert_eq!(alu.get('w').to_string(), "");
-        assert_eq!(alu.get('x').to_string(), "");
-        assert_eq!(alu.get('y').to_string(), "");
-        assert_eq!(alu.get('z').to_string(), "");
+        // a digit is less than 16, so the last mod 2 of w has nothing left to do
+        assert_eq!(alu.get('w').to_string(), "(((i0) / (2)) / (2)) / (2)");
+        assert_eq!(alu.get('x').to_string(), "(((i0) / (2)) / (2)) % (2)");
+        assert_eq!(alu.get('y').to_string(), "((i0) / (2)) % (2)");
+        assert_eq!(alu.get('z').to_string(), "(i0) % (2)");
+
+        // 5 is 0b0101
+        let bits: Vec<i64> = ['w', 'x', 'y', 'z']
+            .iter()
+            .map(|&ch| Ranges::new(&[Some(5)]).of(&alu.get(ch)).0)
+            .collect();
+        assert_eq!(bits, [0, 1, 0, 1]);
     }
 
-    fn real() -> String {
+    #[test]
+    fn test_parse_invalid() {
+        let actual = parse("inp w\nadd x\nmul x 0").err();
+        assert_eq!(
+            actual,
+            Some(ParseError::new(24, 2, 6, "a register or number"))
+        );
+
+        let actual = parse("inp w\nsub x 1").err();
+        assert_eq!(
+            actual,
+            Some(ParseError::new(
+                24,
+                2,
+                1,
+                "one of inp, add, mul, div, mod or eql"
+            ))
+        );
+    }
+
+    pub(super) fn real() -> String {
         util::read_input(24)
     }
 
+    /// One MONAD block, which pushes the digit plus `add_y` onto the base 26 stack in `z` when
+    /// `div_z` is 1, and pops if the digit is the top of the stack plus `add_x` when it's 26.
+    pub(super) fn monad_block(div_z: i64, add_x: i64, add_y: i64) -> String {
+        [
+            "inp w".to_string(),
+            "mul x 0".to_string(),
+            "add x z".to_string(),
+            "mod x 26".to_string(),
+            format!("div z {}", div_z),
+            format!("add x {}", add_x),
+            "eql x w".to_string(),
+            "eql x 0".to_string(),
+            "mul y 0".to_string(),
+            "add y 25".to_string(),
+            "mul y x".to_string(),
+            "add y 1".to_string(),
+            "mul z y".to_string(),
+            "mul y 0".to_string(),
+            "add y w".to_string(),
+            format!("add y {}", add_y),
+            "mul y x".to_string(),
+            "add z y".to_string(),
+        ]
+        .join("\n")
+    }
+
+    pub(super) fn small_monad() -> String {
+        // the second digit has to be the first one plus 3 - 2
+        [monad_block(1, 11, 3), monad_block(26, -2, 4)].join("\n")
+    }
+
+    #[test]
+    fn test_simplify() {
+        let ranges = &mut Ranges::default();
+        let i0 = Rc::new(Expression::Input(0));
+        assert_eq!(Expression::mul(i0.clone(), constant(0)), constant(0));
+        assert_eq!(Expression::add(constant(0), i0.clone()), i0);
+        assert_eq!(Expression::div(ranges, i0.clone(), constant(1)), i0);
+        assert_eq!(
+            Expression::rem(ranges, i0.clone(), constant(1)),
+            constant(0)
+        );
+        assert_eq!(Expression::add(constant(2), constant(3)), constant(5));
+        // a digit is always less than 26
+        assert_eq!(Expression::rem(ranges, i0.clone(), constant(26)), i0);
+        assert_eq!(
+            Expression::div(ranges, i0.clone(), constant(26)),
+            constant(0)
+        );
+    }
+
+    #[test]
+    fn test_simplify_stack() {
+        let ranges = &mut Ranges::default();
+        let i0 = Rc::new(Expression::Input(0));
+        let i1 = Rc::new(Expression::Input(1));
+        let top = Expression::add(i1.clone(), constant(5));
+        let z = Expression::add(Expression::mul(i0.clone(), constant(26)), top.clone());
+        assert_eq!(Expression::rem(ranges, z.clone(), constant(26)), top);
+        assert_eq!(Expression::div(ranges, z, constant(26)), i0);
+    }
+
+    #[test]
+    fn test_eql_ranges() {
+        let ranges = &mut Ranges::default();
+        let i0 = Rc::new(Expression::Input(0));
+        let i1 = Rc::new(Expression::Input(1));
+        let out_of_range = Expression::add(i1.clone(), constant(10));
+        assert_eq!(
+            Expression::eql(ranges, out_of_range, i0.clone()),
+            constant(0)
+        );
+        assert_eq!(Expression::eql(ranges, i0.clone(), i0.clone()), constant(1));
+        let undecided = Expression::eql(ranges, Expression::add(i1, constant(1)), i0);
+        assert_eq!(ranges.of(&undecided), (0, 1));
+        assert_eq!(Ranges::new(&[Some(3), Some(2)]).of(&undecided), (1, 1));
+        assert_eq!(Ranges::new(&[Some(3), Some(3)]).of(&undecided), (0, 0));
+    }
+
+    #[test]
+    fn test_explore() {
+        let paths = parse(&small_monad()).unwrap().explore(PATH_LIMIT).unwrap();
+        assert_eq!(paths.len(), 2);
+        let zs: Vec<_> = paths.iter().map(|p| p.range('z')).collect();
+        assert!(zs.contains(&(0, 0)), "{:?}", zs);
+        assert_eq!(zs.iter().filter(|z| z.0 > 0).count(), 1, "{:?}", zs);
+    }
+
+    #[test]
+    fn test_explore_limit() {
+        // every digit forks on whether it's 5, into 2^14 paths that all leave z at 0
+        let program = ["inp w", "eql w 5", "add z w", "mul z 0"]
+            .repeat(14)
+            .join("\n");
+        let alu = parse(&program).unwrap();
+        assert!(alu.clone().explore(PATH_LIMIT).is_none());
+        assert_eq!(
+            alu.clone().explore(1 << 14).map(|paths| paths.len()),
+            Some(1 << 14)
+        );
+        assert_eq!(part1(&alu), 99999999999999);
+        assert_eq!(part2(&alu), 11111111111111);
+        let paths = parse(&real()).unwrap().explore(PATH_LIMIT).unwrap();
+        assert!(paths.len() <= 128, "{}", paths.len());
+    }
+
+    #[test]
+    fn test_execute() {
+        let alu = parse(&ex1()).unwrap();
+        assert_eq!(alu.execute([7], STEP_LIMIT).unwrap().x, -7);
+
+        // 13 is 0b1101
+        let alu = parse(&ex2()).unwrap();
+        let expected = Registers {
+            w: 1,
+            x: 1,
+            y: 0,
+            z: 1,
+        };
+        assert_eq!(alu.execute([13], STEP_LIMIT), Ok(expected));
+    }
+
+    #[test]
+    fn test_execute_after_run() {
+        // the symbolic run leaves the program for the concrete one
+        let mut alu = parse(&ex2()).unwrap();
+        alu.run();
+        assert_eq!(alu.get('z').to_string(), "(i0) % (2)");
+        assert_eq!(alu.execute([13], STEP_LIMIT).map(|r| r.z), Ok(1));
+        assert_eq!(alu.compile().run(&[13]).map(|r| r.z), Ok(1));
+        // and running again finds nothing left to do
+        alu.run();
+        assert_eq!(alu.get('z').to_string(), "(i0) % (2)");
+    }
+
+    #[test]
+    fn test_execute_errors() {
+        let run = |program: &[&str], inputs: &[i64], limit| {
+            let alu = parse(&program.join("\n")).unwrap();
+            alu.execute(inputs.iter().copied(), limit).unwrap_err()
+        };
+        assert_eq!(
+            run(&["inp x", "div x y"], &[1], STEP_LIMIT),
+            AluError::DivisionByZero { step: 2 }
+        );
+        assert_eq!(
+            run(&["add x -3", "mod x 2"], &[], STEP_LIMIT),
+            AluError::InvalidModulo {
+                step: 2,
+                a: -3,
+                b: 2
+            }
+        );
+        assert_eq!(
+            run(&["add x 5", "mod x 0"], &[], STEP_LIMIT),
+            AluError::InvalidModulo {
+                step: 2,
+                a: 5,
+                b: 0
+            }
+        );
+        assert_eq!(
+            run(&["add x 9223372036854775807", "add x 1"], &[], STEP_LIMIT),
+            AluError::Overflow { step: 2 }
+        );
+        assert_eq!(
+            run(&["inp x", "inp y"], &[1], STEP_LIMIT),
+            AluError::OutOfInput { step: 2 }
+        );
+        assert_eq!(
+            run(&["inp x", "add x 1", "add x 1"], &[1], 2),
+            AluError::StepLimit { limit: 2 }
+        );
+        assert_eq!(
+            AluError::InvalidModulo {
+                step: 2,
+                a: -3,
+                b: 2
+            }
+            .to_string(),
+            "invalid mod -3 2 at step 2"
+        );
+    }
+
+    #[test]
+    fn test_accepts() {
+        let alu = parse(&small_monad()).unwrap();
+        let compiled = alu.compile();
+        assert_eq!(compiled.accepts(89), Ok(true));
+        assert_eq!(compiled.accepts(88), Ok(false));
+        assert_eq!(compiled.accepts(10), Ok(false));
+        assert_eq!(compiled.accepts(-12), Ok(false));
+        // one digit per inp
+        assert_eq!(compiled.accepts(1), Ok(false));
+        assert_eq!(compiled.accepts(123), Ok(false));
+    }
+
+    /// The symbolic run, with every input filled in, has to agree with the concrete one.
+    #[test]
+    fn test_symbolic_matches_execute() {
+        for program in [ex2(), small_monad()] {
+            let mut symbolic = parse(&program).unwrap();
+            let concrete = symbolic.clone();
+            symbolic.run();
+            let inputs = symbolic.next_input as usize;
+            for n in 0..9usize.pow(inputs as u32) {
+                let digits: Vec<i64> = (0..inputs)
+                    .map(|i| (n / 9usize.pow(i as u32) % 9 + 1) as i64)
+                    .collect();
+                let fixed: Vec<_> = digits.iter().map(|&d| Some(d)).collect();
+                let registers = concrete.execute(digits.clone(), STEP_LIMIT).unwrap();
+                let mut ranges = Ranges::new(&fixed);
+                for ch in ['w', 'x', 'y', 'z'] {
+                    let (value, _) = ranges.of(&symbolic.get(ch));
+                    assert_eq!(value, registers.get(ch), "{} with {:?}", ch, digits);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_display_operations() {
+        let alu = parse(&real()).unwrap();
+        let actual: Vec<String> = alu.program.iter().map(|op| op.to_string()).collect();
+        assert_eq!(actual.join("\n"), real().trim_end());
+    }
+
+    #[test]
+    fn test_explain_blocks() {
+        let actual = explain(&parse(&small_monad()).unwrap(), "blocks").unwrap();
+        let expected = ["    d1 = d0 + 1", "largest: 89", "smallest: 12"].join("\n");
+        assert!(actual.ends_with(&expected), "{}", actual);
+        assert_eq!(
+            explain(&parse(&ex2()).unwrap(), "blocks"),
+            Err("Not a MONAD program: expected mul x 0 at step 2, found add z w".to_string())
+        );
+        assert!(explain(&parse(&ex2()).unwrap(), "tree").is_err());
+    }
+
+    #[test]
+    fn test_explain_expressions() {
+        let alu = parse(&ex1()).unwrap();
+        assert_eq!(explain(&alu, "infix"), Ok("0".to_string()));
+        let alu = parse("inp z\nmul z -1").unwrap();
+        assert_eq!(explain(&alu, "infix"), Ok("(i0) * (-1)".to_string()));
+        assert_eq!(explain(&alu, "pretty"), Ok("(* i0 -1)".to_string()));
+        assert!(explain(&alu, "dot")
+            .unwrap()
+            .starts_with("digraph expression {"));
+        let rust = explain(&alu, "rust").unwrap();
+        assert!(rust.contains("let t2 = i[0] * -1;"), "{}", rust);
+        let c = explain(&alu, "c").unwrap();
+        assert!(c.contains("return t2;"), "{}", c);
+    }
+
+    #[test]
+    fn test_explain_counts() {
+        let alu = parse("inp z\nmul z -1").unwrap();
+        let expected = "as a tree: 3\ndistinct: 3\ninterned: 4\nranges worked out: 0";
+        assert_eq!(explain(&alu, "counts"), Ok(expected.to_string()));
+    }
+
+    #[test]
+    fn test_part1_small_monad() {
+        let actual = part1(&parse(&small_monad()).unwrap());
+        assert_eq!(actual, 89);
+    }
+
     #[test]
     fn test_part1_real() {
-        let actual = part1(&parse(&real()));
-        assert_eq!(actual, 0); // 83719996499591 is too high
-                               // 19599469991738 is too low
-                               // 29599458991739 is too low
+        let actual = part1(&parse(&real()).unwrap());
+        assert_eq!(actual, 29599469991739);
     }
 
     #[test]
     fn test_part2_ex1() {
-        let actual = part2(&parse(&ex1()));
-        assert_eq!(actual, 0);
+        // z is never touched, so any digit will do
+        let actual = part2(&parse(&ex1()).unwrap());
+        assert_eq!(actual, 1);
+    }
+
+    #[test]
+    fn test_part2_small_monad() {
+        let actual = part2(&parse(&small_monad()).unwrap());
+        assert_eq!(actual, 12);
     }
 
     #[test]
     fn test_part2_real() {
-        let actual = part2(&parse(&real()));
-        assert_eq!(actual, 0);
+        let actual = part2(&parse(&real()).unwrap());
+        assert_eq!(actual, 17153114691118);
     }
 }
@@ -0,0 +1,462 @@
+use super::{AluError, Operation, Registers, Value, ALU, STEP_LIMIT};
+use std::collections::HashSet;
+
+const REGISTERS: [char; 4] = ['w', 'x', 'y', 'z'];
+
+fn register(ch: char) -> usize {
+    REGISTERS.iter().position(|&r| r == ch).unwrap_or(3)
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Operand {
+    Register(usize),
+    Literal(i64),
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Instruction {
+    Inp(usize),
+    Set(usize, i64),
+    Add(usize, Operand),
+    Mul(usize, Operand),
+    Div(usize, Operand),
+    Mod(usize, Operand),
+    Eql(usize, Operand),
+}
+
+/// An `Instruction` with the kind of its operand picked out ahead of time, which is what actually
+/// runs.
+#[derive(Clone, Copy, Debug)]
+enum Op {
+    Inp(usize),
+    Set(usize, i64),
+    AddRegister(usize, usize),
+    Add(usize, i64),
+    MulRegister(usize, usize),
+    Mul(usize, i64),
+    DivRegister(usize, usize),
+    Div(usize, i64),
+    ModRegister(usize, usize),
+    Mod(usize, i64),
+    EqlRegister(usize, usize),
+    Eql(usize, i64),
+}
+
+impl Op {
+    fn lower(instruction: Instruction) -> Op {
+        use Operand::{Literal, Register};
+        match instruction {
+            Instruction::Inp(r) => Op::Inp(r),
+            Instruction::Set(r, c) => Op::Set(r, c),
+            Instruction::Add(r, Register(b)) => Op::AddRegister(r, b),
+            Instruction::Add(r, Literal(c)) => Op::Add(r, c),
+            Instruction::Mul(r, Register(b)) => Op::MulRegister(r, b),
+            Instruction::Mul(r, Literal(c)) => Op::Mul(r, c),
+            Instruction::Div(r, Register(b)) => Op::DivRegister(r, b),
+            Instruction::Div(r, Literal(c)) => Op::Div(r, c),
+            Instruction::Mod(r, Register(b)) => Op::ModRegister(r, b),
+            Instruction::Mod(r, Literal(c)) => Op::Mod(r, c),
+            Instruction::Eql(r, Register(b)) => Op::EqlRegister(r, b),
+            Instruction::Eql(r, Literal(c)) => Op::Eql(r, c),
+        }
+    }
+
+    /// Runs this on `registers`, or returns `None` for anything that's an error on a real ALU.
+    #[inline]
+    fn run(self, registers: &mut [i64; 4], input: i64) -> Option<()> {
+        fn rem(a: i64, b: i64) -> Option<i64> {
+            (a >= 0 && b > 0).then(|| a % b)
+        }
+        match self {
+            Op::Inp(r) => registers[r] = input,
+            Op::Set(r, c) => registers[r] = c,
+            Op::AddRegister(r, b) => registers[r] = registers[r].checked_add(registers[b])?,
+            Op::Add(r, c) => registers[r] = registers[r].checked_add(c)?,
+            Op::MulRegister(r, b) => registers[r] = registers[r].checked_mul(registers[b])?,
+            Op::Mul(r, c) => registers[r] = registers[r].checked_mul(c)?,
+            Op::DivRegister(r, b) => registers[r] = registers[r].checked_div(registers[b])?,
+            Op::Div(r, c) => registers[r] = registers[r].checked_div(c)?,
+            Op::ModRegister(r, b) => registers[r] = rem(registers[r], registers[b])?,
+            Op::Mod(r, c) => registers[r] = rem(registers[r], c)?,
+            Op::EqlRegister(r, b) => registers[r] = (registers[r] == registers[b]) as i64,
+            Op::Eql(r, c) => registers[r] = (registers[r] == c) as i64,
+        }
+        Some(())
+    }
+}
+
+/// The instructions from one `inp` up to the next, and the registers whose value on entry can
+/// still change the final `z`.
+#[derive(Clone, Debug)]
+struct Block {
+    code: Vec<Instruction>,
+    ops: Vec<Op>,
+    live: [bool; 4],
+}
+
+/// An ALU program turned into straight-line code over an array of registers, one block per
+/// `inp`. Blocks can be run one at a time, so the state between them can be memoized.
+pub struct Compiled<'a> {
+    alu: &'a ALU,
+    prelude: Vec<Op>,
+    blocks: Vec<Block>,
+}
+
+fn operand(v: &Value) -> Operand {
+    match v {
+        Value::Literal(i) => Operand::Literal(*i),
+        Value::Variable(ch) => Operand::Register(register(*ch)),
+    }
+}
+
+fn translate(op: &Operation) -> Instruction {
+    match op {
+        Operation::Inp(ch) => Instruction::Inp(register(*ch)),
+        Operation::Add(ch, v) => Instruction::Add(register(*ch), operand(v)),
+        Operation::Mul(ch, v) => Instruction::Mul(register(*ch), operand(v)),
+        Operation::Div(ch, v) => Instruction::Div(register(*ch), operand(v)),
+        Operation::Mod(ch, v) => Instruction::Mod(register(*ch), operand(v)),
+        Operation::Eql(ch, v) => Instruction::Eql(register(*ch), operand(v)),
+    }
+}
+
+impl Instruction {
+    /// The registers this reads, and the one it writes. `mul r 0` doesn't depend on `r`.
+    fn uses(&self) -> (Vec<usize>, usize) {
+        match *self {
+            Instruction::Inp(r) | Instruction::Set(r, _) => (vec![], r),
+            Instruction::Mul(r, Operand::Literal(0)) => (vec![], r),
+            Instruction::Add(r, b)
+            | Instruction::Mul(r, b)
+            | Instruction::Div(r, b)
+            | Instruction::Mod(r, b)
+            | Instruction::Eql(r, b) => match b {
+                Operand::Register(b) => (vec![r, b], r),
+                Operand::Literal(_) => (vec![r], r),
+            },
+        }
+    }
+}
+
+/// Replaces instructions whose result is already known with `Set`, starting from `known`
+/// registers. Errors are left for the instructions to find at run time.
+fn propagate_constants(code: &mut [Instruction], mut known: [Option<i64>; 4]) {
+    for instruction in code.iter_mut() {
+        let (_, r) = instruction.uses();
+        let mut registers = [0; 4];
+        let mut all_known = true;
+        for used in instruction.uses().0 {
+            match known[used] {
+                Some(v) => registers[used] = v,
+                None => all_known = false,
+            }
+        }
+        known[r] = match instruction {
+            Instruction::Inp(_) => None,
+            _ if all_known => match Op::lower(*instruction).run(&mut registers, 0) {
+                Some(()) => {
+                    *instruction = Instruction::Set(r, registers[r]);
+                    Some(registers[r])
+                }
+                None => None,
+            },
+            _ => None,
+        };
+    }
+}
+
+/// Drops instructions that don't change anything, and results that can't be needed before the
+/// registers in `live` are read, leaving the ones that could still fail.
+fn remove_dead_code(code: &mut Vec<Instruction>, mut live: [bool; 4]) {
+    let mut kept = vec![];
+    for instruction in code.drain(..).rev() {
+        let (reads, writes) = instruction.uses();
+        let dead = match instruction {
+            Instruction::Add(_, Operand::Literal(0))
+            | Instruction::Mul(_, Operand::Literal(1))
+            | Instruction::Div(_, Operand::Literal(1)) => true,
+            Instruction::Set(..) | Instruction::Eql(..) => !live[writes],
+            _ => false,
+        };
+        if dead {
+            continue;
+        }
+        live[writes] = false;
+        for r in reads {
+            live[r] = true;
+        }
+        kept.push(instruction);
+    }
+    kept.reverse();
+    *code = kept;
+}
+
+impl ALU {
+    pub fn compile(&self) -> Compiled<'_> {
+        let code: Vec<Instruction> = self.program.iter().map(translate).collect();
+        let mut starts: Vec<usize> = code
+            .iter()
+            .enumerate()
+            .filter(|(_, i)| matches!(i, Instruction::Inp(_)))
+            .map(|(n, _)| n)
+            .collect();
+        let prelude_end = starts.first().copied().unwrap_or(code.len());
+        starts.push(code.len());
+
+        // work backwards from the end, where only z matters
+        let mut live = [false, false, false, true];
+        let mut blocks = vec![];
+        for window in starts.windows(2).rev() {
+            let block = &code[window[0]..window[1]];
+            for instruction in block.iter().rev() {
+                let (reads, writes) = instruction.uses();
+                live[writes] = false;
+                for r in reads {
+                    live[r] = true;
+                }
+            }
+            blocks.push(Block {
+                code: block.to_vec(),
+                ops: vec![],
+                live,
+            });
+        }
+        blocks.reverse();
+
+        // dead registers are zeroed between blocks, so they are known constants
+        let mut live_out = [false, false, false, true];
+        for block in blocks.iter_mut().rev() {
+            let known = [0, 1, 2, 3].map(|r| if block.live[r] { None } else { Some(0) });
+            propagate_constants(&mut block.code, known);
+            remove_dead_code(&mut block.code, live_out);
+            block.ops = block.code.iter().map(|&i| Op::lower(i)).collect();
+            live_out = block.live;
+        }
+        let mut prelude = code[..prelude_end].to_vec();
+        propagate_constants(&mut prelude, [Some(0); 4]);
+        remove_dead_code(&mut prelude, live_out);
+
+        Compiled {
+            alu: self,
+            prelude: prelude.into_iter().map(Op::lower).collect(),
+            blocks,
+        }
+    }
+}
+
+impl Compiled<'_> {
+    fn registers(values: [i64; 4]) -> Registers {
+        let [w, x, y, z] = values;
+        Registers { w, x, y, z }
+    }
+
+    /// The registers after the instructions before the first `inp`, or `None` on an error.
+    fn start(&self) -> Option<[i64; 4]> {
+        let mut registers = [0; 4];
+        for op in &self.prelude {
+            op.run(&mut registers, 0)?;
+        }
+        Some(registers)
+    }
+
+    /// `registers` with the ones that can't affect the result from `block` on zeroed, so that
+    /// equivalent states compare equal.
+    fn canonical(&self, block: usize, mut registers: [i64; 4]) -> [i64; 4] {
+        for (r, live) in self.blocks[block].live.iter().enumerate() {
+            if !live {
+                registers[r] = 0;
+            }
+        }
+        registers
+    }
+
+    /// Runs one block from `registers`, with `input` for its `inp`.
+    fn run_block(&self, block: usize, registers: [i64; 4], input: i64) -> Option<[i64; 4]> {
+        let mut registers = self.canonical(block, registers);
+        for op in &self.blocks[block].ops {
+            op.run(&mut registers, input)?;
+        }
+        Some(registers)
+    }
+
+    /// The number of `inp` instructions.
+    pub fn inputs(&self) -> usize {
+        self.blocks.len()
+    }
+
+    /// Runs the whole program on `inputs`. The final `z` is the same as [`ALU::execute`]'s, though
+    /// registers that no longer matter by then may differ. An error is reported by running the
+    /// program again on the slower interpreter, which knows where it went wrong.
+    pub fn run(&self, inputs: &[i64]) -> Result<Registers, AluError> {
+        let fast = || {
+            let mut registers = self.start()?;
+            for (block, &input) in inputs.iter().enumerate().take(self.inputs()) {
+                registers = self.run_block(block, registers, input)?;
+            }
+            Some(registers).filter(|_| inputs.len() >= self.inputs())
+        };
+        match fast() {
+            Some(registers) => Ok(Self::registers(registers)),
+            None => self.alu.execute(inputs.iter().copied(), STEP_LIMIT),
+        }
+    }
+
+    /// Whether MONAD accepts `model_number`, which has to have one digit from 1 to 9 for each
+    /// `inp`.
+    pub fn accepts(&self, model_number: i64) -> Result<bool, AluError> {
+        let digits: Vec<i64> = model_number
+            .to_string()
+            .chars()
+            .map(|ch| ch.to_digit(10).unwrap_or(0) as i64)
+            .collect();
+        if model_number <= 0 || digits.contains(&0) || digits.len() != self.inputs() {
+            return Ok(false);
+        }
+        Ok(self.run(&digits)?.z == 0)
+    }
+
+    /// The first digits, in the order of `digits` at each position, that leave 0 in `z`. States
+    /// that are known to lead nowhere are remembered, so each is only explored once.
+    pub fn search(&self, digits: &[i64]) -> Option<Vec<i64>> {
+        fn go(
+            compiled: &Compiled,
+            block: usize,
+            registers: [i64; 4],
+            digits: &[i64],
+            dead: &mut HashSet<(usize, [i64; 4])>,
+            found: &mut Vec<i64>,
+        ) -> bool {
+            if block == compiled.inputs() {
+                return registers[3] == 0;
+            }
+            let key = compiled.canonical(block, registers);
+            if dead.contains(&(block, key)) {
+                return false;
+            }
+            for &d in digits {
+                if let Some(next) = compiled.run_block(block, key, d) {
+                    found.push(d);
+                    if go(compiled, block + 1, next, digits, dead, found) {
+                        return true;
+                    }
+                    found.pop();
+                }
+            }
+            dead.insert((block, key));
+            false
+        }
+
+        let mut found = vec![];
+        let start = self.start()?;
+        go(self, 0, start, digits, &mut HashSet::new(), &mut found).then_some(found)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::tests::{ex2, real, small_monad};
+    use super::super::*;
+    use super::{Instruction, Operand};
+
+    #[test]
+    fn test_live_registers() {
+        let alu = parse(&real()).unwrap();
+        let compiled = alu.compile();
+        assert_eq!(compiled.inputs(), 14);
+        // every MONAD block starts from scratch apart from z
+        for block in &compiled.blocks {
+            assert_eq!(block.live, [false, false, false, true]);
+        }
+    }
+
+    #[test]
+    fn test_block_code() {
+        use Instruction::*;
+        use Operand::*;
+        let (w, x, y, z) = (0, 1, 2, 3);
+
+        let alu = parse(&small_monad()).unwrap();
+        let compiled = alu.compile();
+        assert_eq!(
+            compiled.blocks[0].code,
+            [
+                Inp(w),
+                Set(x, 0),
+                Add(x, Register(z)),
+                Mod(x, Literal(26)),
+                // div z 1 is gone
+                Add(x, Literal(11)),
+                Eql(x, Register(w)),
+                Eql(x, Literal(0)),
+                // so is the first mul y 0, as add y 25 overwrites it
+                Set(y, 25),
+                Mul(y, Register(x)),
+                Add(y, Literal(1)),
+                Mul(z, Register(y)),
+                Set(y, 0),
+                Add(y, Register(w)),
+                Add(y, Literal(3)),
+                Mul(y, Register(x)),
+                Add(z, Register(y)),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_run_matches_execute() {
+        let alu = parse(&real()).unwrap();
+        let compiled = alu.compile();
+        let mut digits = [9; 14];
+        for n in 0..2000 {
+            // count down through the digits 1 to 9
+            for (i, d) in digits.iter_mut().enumerate() {
+                *d = 9 - (n / 9usize.pow(i as u32 % 4) + i) as i64 % 9;
+            }
+            let expected = alu.execute(digits, STEP_LIMIT).unwrap().z;
+            assert_eq!(compiled.run(&digits).unwrap().z, expected, "{:?}", digits);
+        }
+    }
+
+    #[test]
+    fn test_run_errors() {
+        let alu = parse(&["inp x", "mod x y"].join("\n")).unwrap();
+        let compiled = alu.compile();
+        assert_eq!(
+            compiled.run(&[4]),
+            Err(AluError::InvalidModulo {
+                step: 2,
+                a: 4,
+                b: 0
+            })
+        );
+        assert_eq!(compiled.run(&[]), Err(AluError::OutOfInput { step: 1 }));
+
+        let alu = parse(&ex2()).unwrap();
+        let expected = alu.execute([13], STEP_LIMIT).unwrap();
+        assert_eq!(alu.compile().run(&[13]), Ok(expected));
+    }
+
+    #[test]
+    fn test_search() {
+        let alu = parse(&small_monad()).unwrap();
+        let compiled = alu.compile();
+        assert_eq!(
+            compiled.search(&[9, 8, 7, 6, 5, 4, 3, 2, 1]),
+            Some(vec![8, 9])
+        );
+        assert_eq!(
+            compiled.search(&[1, 2, 3, 4, 5, 6, 7, 8, 9]),
+            Some(vec![1, 2])
+        );
+        assert_eq!(compiled.search(&[9]), None);
+    }
+
+    #[test]
+    fn test_accepts() {
+        let alu = parse(&real()).unwrap();
+        let compiled = alu.compile();
+        assert_eq!(compiled.accepts(29599469991739), Ok(true));
+        assert_eq!(compiled.accepts(29599469991749), Ok(false));
+        assert_eq!(compiled.accepts(2959946999173), Ok(false));
+    }
+}
@@ -0,0 +1,367 @@
+use super::ALU;
+use std::fmt;
+
+/// The block MONAD repeats for every digit, with `_` for the literals that differ between blocks:
+/// `div z`, `add x` and `add y`, in that order.
+const TEMPLATE: [&str; 18] = [
+    "inp w", "mul x 0", "add x z", "mod x 26", "div z _", "add x _", "eql x w", "eql x 0",
+    "mul y 0", "add y 25", "mul y x", "add y 1", "mul z y", "mul y 0", "add y w", "add y _",
+    "mul y x", "add z y",
+];
+
+/// What a block does to z, read as a stack of base 26 digits.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Kind {
+    /// Always pushes its digit plus `add_y`, since no digit can match `add_x`.
+    Push,
+    /// Pops the top, and pushes its own digit back unless that matches the top plus `add_x`.
+    Pop,
+}
+
+/// The literals of one block.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct Block {
+    pub kind: Kind,
+    pub div_z: i64,
+    pub add_x: i64,
+    pub add_y: i64,
+}
+
+/// Digit `pop` has to be digit `push` plus `offset` for the pop not to push anything back.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct Constraint {
+    pub push: usize,
+    pub pop: usize,
+    pub offset: i64,
+}
+
+/// The blocks of a MONAD program, and what its digits have to satisfy for z to end up 0.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Analysis {
+    pub blocks: Vec<Block>,
+    pub constraints: Vec<Constraint>,
+}
+
+/// Why a program doesn't have the structure of MONAD. `step` counts instructions from 1.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum AnalysisError {
+    /// The instruction at `step` isn't the one in the template.
+    Mismatch {
+        step: usize,
+        expected: String,
+        found: String,
+    },
+    /// The block ends before the template does.
+    Truncated { block: usize },
+    /// The block neither always pushes nor pops.
+    Unclassified { block: usize },
+    /// A pop with nothing pushed, or a push that's never popped.
+    Unbalanced { block: usize },
+}
+
+impl fmt::Display for AnalysisError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            AnalysisError::Mismatch {
+                step,
+                expected,
+                found,
+            } => write!(f, "expected {} at step {}, found {}", expected, step, found),
+            AnalysisError::Truncated { block } => write!(f, "block {} is cut short", block),
+            AnalysisError::Unclassified { block } => {
+                write!(f, "block {} is neither a push nor a pop", block)
+            }
+            AnalysisError::Unbalanced { block } => {
+                write!(f, "block {} leaves the stack unbalanced", block)
+            }
+        }
+    }
+}
+
+/// Compares one instruction against a template line, returning the literal that stands in for a
+/// `_`, if there is one.
+fn diff(step: usize, expected: &str, found: String) -> Result<Option<i64>, AnalysisError> {
+    let mismatch = || AnalysisError::Mismatch {
+        step,
+        expected: expected.to_string(),
+        found: found.clone(),
+    };
+    let (expected_words, found_words): (Vec<&str>, Vec<&str>) =
+        (expected.split(' ').collect(), found.split(' ').collect());
+    if expected_words.len() != found_words.len() {
+        return Err(mismatch());
+    }
+    let mut literal = None;
+    for (e, f) in expected_words.iter().zip(&found_words) {
+        match *e {
+            "_" => literal = Some(f.parse().map_err(|_| mismatch())?),
+            _ if e == f => {}
+            _ => return Err(mismatch()),
+        }
+    }
+    Ok(literal)
+}
+
+fn classify(block: usize, div_z: i64, add_x: i64, add_y: i64) -> Result<Block, AnalysisError> {
+    let kind = match div_z {
+        // z % 26 + add_x can't be a digit, and digit + add_y fits in a base 26 digit
+        1 if add_x > 9 && (0..=16).contains(&add_y) => Kind::Push,
+        26 => Kind::Pop,
+        _ => return Err(AnalysisError::Unclassified { block }),
+    };
+    Ok(Block {
+        kind,
+        div_z,
+        add_x,
+        add_y,
+    })
+}
+
+/// The program split into blocks that each start at an `inp`, with the step of their first
+/// instruction. Anything before the first `inp` is a block of its own.
+fn split_blocks(program: &[String]) -> Vec<(usize, &[String])> {
+    let mut starts: Vec<usize> = (0..program.len())
+        .filter(|&i| i == 0 || program[i].starts_with("inp "))
+        .collect();
+    starts.push(program.len());
+    starts
+        .windows(2)
+        .map(|w| (w[0] + 1, &program[w[0]..w[1]]))
+        .collect()
+}
+
+/// Splits a MONAD program into its blocks, and pairs up the pushes and pops.
+pub fn analyze(alu: &ALU) -> Result<Analysis, AnalysisError> {
+    let program: Vec<String> = alu.program.iter().map(|op| op.to_string()).collect();
+
+    let mut blocks = vec![];
+    for (block, (first_step, ops)) in split_blocks(&program).into_iter().enumerate() {
+        let mut literals = vec![];
+        for (i, (expected, found)) in TEMPLATE.iter().zip(ops).enumerate() {
+            literals.extend(diff(first_step + i, expected, found.clone())?);
+        }
+        if ops.len() < TEMPLATE.len() {
+            return Err(AnalysisError::Truncated { block });
+        }
+        if let Some(extra) = ops.get(TEMPLATE.len()) {
+            // the template is over, so this should have been the next block's inp
+            return Err(AnalysisError::Mismatch {
+                step: first_step + TEMPLATE.len(),
+                expected: TEMPLATE[0].to_string(),
+                found: extra.clone(),
+            });
+        }
+        blocks.push(classify(block, literals[0], literals[1], literals[2])?);
+    }
+
+    let mut stack = vec![];
+    let mut constraints = vec![];
+    for (pop, block) in blocks.iter().enumerate() {
+        match block.kind {
+            Kind::Push => stack.push(pop),
+            Kind::Pop => {
+                let push = stack
+                    .pop()
+                    .ok_or(AnalysisError::Unbalanced { block: pop })?;
+                constraints.push(Constraint {
+                    push,
+                    pop,
+                    offset: blocks[push].add_y + block.add_x,
+                });
+            }
+        }
+    }
+    if let Some(&block) = stack.first() {
+        return Err(AnalysisError::Unbalanced { block });
+    }
+    constraints.sort_by_key(|c| c.push);
+
+    Ok(Analysis {
+        blocks,
+        constraints,
+    })
+}
+
+impl Analysis {
+    /// The first digits that satisfy every constraint, trying `digits` in order for each pushed
+    /// digit, or `None` if some constraint can't be met by digits from 1 to 9.
+    pub fn model_number(&self, digits: &[i64]) -> Option<Vec<i64>> {
+        let mut number = vec![0; self.blocks.len()];
+        for c in &self.constraints {
+            let d = digits
+                .iter()
+                .find(|&&d| (1..=9).contains(&(d + c.offset)))?;
+            number[c.push] = *d;
+            number[c.pop] = d + c.offset;
+        }
+        Some(number)
+    }
+}
+
+/// The template, then a table of the literals in each block, then the constraints.
+impl fmt::Display for Analysis {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "template:")?;
+        for line in TEMPLATE {
+            writeln!(f, "    {}", line)?;
+        }
+        writeln!(f, "block  kind  div z  add x  add y")?;
+        for (i, block) in self.blocks.iter().enumerate() {
+            let kind = match block.kind {
+                Kind::Push => "push",
+                Kind::Pop => "pop",
+            };
+            writeln!(
+                f,
+                "{:>5}  {:<4}  {:>5}  {:>5}  {:>5}",
+                i, kind, block.div_z, block.add_x, block.add_y
+            )?;
+        }
+        write!(f, "constraints:")?;
+        for c in &self.constraints {
+            let sign = if c.offset < 0 { '-' } else { '+' };
+            write!(
+                f,
+                "\n    d{} = d{} {} {}",
+                c.pop,
+                c.push,
+                sign,
+                c.offset.abs()
+            )?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::tests::{ex2, monad_block, real, small_monad};
+    use super::super::{model_number, parse};
+    use super::*;
+
+    #[test]
+    fn test_analyze_small_monad() {
+        let actual = analyze(&parse(&small_monad()).unwrap()).unwrap();
+        assert_eq!(
+            actual.blocks,
+            [
+                Block {
+                    kind: Kind::Push,
+                    div_z: 1,
+                    add_x: 11,
+                    add_y: 3
+                },
+                Block {
+                    kind: Kind::Pop,
+                    div_z: 26,
+                    add_x: -2,
+                    add_y: 4
+                },
+            ]
+        );
+        assert_eq!(
+            actual.constraints,
+            [Constraint {
+                push: 0,
+                pop: 1,
+                offset: 1
+            }]
+        );
+        assert_eq!(
+            actual.model_number(&[9, 8, 7, 6, 5, 4, 3, 2, 1]),
+            Some(vec![8, 9])
+        );
+        assert_eq!(
+            actual.model_number(&[1, 2, 3, 4, 5, 6, 7, 8, 9]),
+            Some(vec![1, 2])
+        );
+    }
+
+    #[test]
+    fn test_analyze_real() {
+        let alu = parse(&real()).unwrap();
+        let actual = analyze(&alu).unwrap();
+        assert_eq!(actual.blocks.len(), 14);
+        assert_eq!(actual.constraints.len(), 7);
+        let largest = actual.model_number(&[9, 8, 7, 6, 5, 4, 3, 2, 1]).unwrap();
+        assert_eq!(model_number(&largest), 29599469991739);
+        let smallest = actual.model_number(&[1, 2, 3, 4, 5, 6, 7, 8, 9]).unwrap();
+        assert_eq!(model_number(&smallest), 17153114691118);
+    }
+
+    #[test]
+    fn test_analyze_errors() {
+        let alu = parse(&ex2()).unwrap();
+        assert_eq!(
+            analyze(&alu),
+            Err(AnalysisError::Mismatch {
+                step: 2,
+                expected: "mul x 0".to_string(),
+                found: "add z w".to_string()
+            })
+        );
+        let alu = parse(&monad_block(1, 11, 3)).unwrap();
+        assert_eq!(analyze(&alu), Err(AnalysisError::Unbalanced { block: 0 }));
+        let alu = parse(&monad_block(26, 11, 3)).unwrap();
+        assert_eq!(analyze(&alu), Err(AnalysisError::Unbalanced { block: 0 }));
+        let alu = parse(&monad_block(1, 2, 3)).unwrap();
+        assert_eq!(analyze(&alu), Err(AnalysisError::Unclassified { block: 0 }));
+        let alu = parse(&[monad_block(1, 11, 3), "inp w".to_string()].join("\n")).unwrap();
+        assert_eq!(analyze(&alu), Err(AnalysisError::Truncated { block: 1 }));
+    }
+
+    #[test]
+    fn test_split_at_inp() {
+        // a block that's one short ends at the next inp, instead of taking it from the next block
+        let short = monad_block(1, 11, 3)
+            .rsplit_once('\n')
+            .unwrap()
+            .0
+            .to_string();
+        let alu = parse(&[short, monad_block(26, -2, 4)].join("\n")).unwrap();
+        assert_eq!(analyze(&alu), Err(AnalysisError::Truncated { block: 0 }));
+
+        let long = [monad_block(1, 11, 3), "add z 0".to_string()].join("\n");
+        let alu = parse(&[long, monad_block(26, -2, 4)].join("\n")).unwrap();
+        assert_eq!(
+            analyze(&alu),
+            Err(AnalysisError::Mismatch {
+                step: 19,
+                expected: "inp w".to_string(),
+                found: "add z 0".to_string()
+            })
+        );
+
+        let alu = parse(&["add z 1", &small_monad()].join("\n")).unwrap();
+        assert_eq!(
+            analyze(&alu),
+            Err(AnalysisError::Mismatch {
+                step: 1,
+                expected: "inp w".to_string(),
+                found: "add z 1".to_string()
+            })
+        );
+    }
+
+    #[test]
+    fn test_impossible_constraint() {
+        let alu = parse(&[monad_block(1, 11, 16), monad_block(26, 0, 0)].join("\n")).unwrap();
+        assert_eq!(analyze(&alu).unwrap().model_number(&[1, 2, 3]), None);
+    }
+
+    #[test]
+    fn test_display() {
+        let alu = parse(&small_monad()).unwrap();
+        let actual = analyze(&alu).unwrap().to_string();
+        let table = [
+            "block  kind  div z  add x  add y",
+            "    0  push      1     11      3",
+            "    1  pop      26     -2      4",
+            "constraints:",
+            "    d1 = d0 + 1",
+        ]
+        .join("\n");
+        assert!(actual.starts_with("template:\n    inp w\n"));
+        assert!(actual.ends_with(&table), "{}", actual);
+    }
+}
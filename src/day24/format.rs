@@ -0,0 +1,261 @@
+use super::Expression;
+use std::collections::HashMap;
+use std::fmt;
+use std::rc::Rc;
+
+/// A language to write an expression in as source code.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Language {
+    Rust,
+    C,
+}
+
+impl Expression {
+    pub(super) fn symbol(&self) -> &'static str {
+        match self {
+            Expression::Constant(_) | Expression::Input(_) => "",
+            Expression::Add(..) => "+",
+            Expression::Mul(..) => "*",
+            Expression::Div(..) => "/",
+            Expression::Mod(..) => "%",
+            Expression::Eql(..) => "==",
+        }
+    }
+
+    pub(super) fn operands(&self) -> Option<(&'static str, &Rc<Expression>, &Rc<Expression>)> {
+        match self {
+            Expression::Constant(_) | Expression::Input(_) => None,
+            Expression::Add(lhs, rhs)
+            | Expression::Mul(lhs, rhs)
+            | Expression::Div(lhs, rhs)
+            | Expression::Mod(lhs, rhs)
+            | Expression::Eql(lhs, rhs) => Some((self.symbol(), lhs, rhs)),
+        }
+    }
+}
+
+/// Infix algebra, with every operand in parentheses: `((i0) * (-1)) + (5)`.
+impl fmt::Display for Expression {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Expression::Constant(c) => write!(f, "{}", c),
+            Expression::Input(i) => write!(f, "i{}", i),
+            Expression::Add(lhs, rhs)
+            | Expression::Mul(lhs, rhs)
+            | Expression::Div(lhs, rhs)
+            | Expression::Mod(lhs, rhs)
+            | Expression::Eql(lhs, rhs) => write!(f, "({}) {} ({})", lhs, self.symbol(), rhs),
+        }
+    }
+}
+
+/// Numbers the nodes of a tree in the order they are first met, so that a subtree shared
+/// through its `Rc` is only written once.
+pub(super) struct Nodes<'a> {
+    ids: HashMap<*const Expression, usize>,
+    pub order: Vec<&'a Rc<Expression>>,
+}
+
+impl<'a> Nodes<'a> {
+    /// Every node below and including `root`, children before their parents.
+    pub fn new(root: &'a Rc<Expression>) -> Nodes<'a> {
+        let mut nodes = Nodes {
+            ids: HashMap::new(),
+            order: vec![],
+        };
+        nodes.visit(root);
+        nodes
+    }
+
+    fn visit(&mut self, node: &'a Rc<Expression>) {
+        if self.ids.contains_key(&Rc::as_ptr(node)) {
+            return;
+        }
+        if let Some((_, lhs, rhs)) = node.operands() {
+            self.visit(lhs);
+            self.visit(rhs);
+        }
+        self.ids.insert(Rc::as_ptr(node), self.order.len());
+        self.order.push(node);
+    }
+
+    pub fn id(&self, node: &Rc<Expression>) -> usize {
+        self.ids[&Rc::as_ptr(node)]
+    }
+}
+
+impl Expression {
+    /// An s-expression with one operand per line, indented by depth. Operations on two leaves
+    /// stay on one line.
+    pub fn pretty(&self) -> String {
+        fn write(e: &Expression, depth: usize, out: &mut String) {
+            match e.operands() {
+                None => out.push_str(&e.to_string()),
+                Some((op, lhs, rhs)) if lhs.operands().is_none() && rhs.operands().is_none() => {
+                    out.push_str(&format!("({} {} {})", op, lhs, rhs))
+                }
+                Some((op, lhs, rhs)) => {
+                    out.push('(');
+                    out.push_str(op);
+                    for operand in [lhs, rhs] {
+                        out.push('\n');
+                        out.push_str(&"  ".repeat(depth + 1));
+                        write(operand, depth + 1, out);
+                    }
+                    out.push(')');
+                }
+            }
+        }
+
+        let mut out = String::new();
+        write(self, 0, &mut out);
+        out
+    }
+
+    /// A Graphviz graph of the expression, with shared subtrees drawn once.
+    pub fn dot(root: &Rc<Expression>) -> String {
+        let nodes = Nodes::new(root);
+        let mut out = String::from("digraph expression {\n");
+        for (id, node) in nodes.order.iter().enumerate() {
+            let label = match node.operands() {
+                Some((op, _, _)) => op.to_string(),
+                None => node.to_string(),
+            };
+            out.push_str(&format!("    n{} [label=\"{}\"];\n", id, label));
+            if let Some((_, lhs, rhs)) = node.operands() {
+                for operand in [lhs, rhs] {
+                    out.push_str(&format!("    n{} -> n{};\n", id, nodes.id(operand)));
+                }
+            }
+        }
+        out.push('}');
+        out
+    }
+
+    /// A function that computes the expression from an array of the inputs, with one variable per
+    /// node so that shared subtrees are only computed once.
+    pub fn source(root: &Rc<Expression>, language: Language) -> String {
+        let nodes = Nodes::new(root);
+        let term = |node: &Rc<Expression>| match node.as_ref() {
+            Expression::Constant(c) => c.to_string(),
+            Expression::Input(i) => format!("i[{}]", i),
+            _ => format!("t{}", nodes.id(node)),
+        };
+        let inputs = nodes
+            .order
+            .iter()
+            .filter_map(|node| match node.as_ref() {
+                Expression::Input(i) => Some(*i as usize + 1),
+                _ => None,
+            })
+            .max()
+            .unwrap_or(0);
+
+        let mut out = match language {
+            Language::Rust => format!("fn monad(i: &[i64; {}]) -> i64 {{\n", inputs),
+            Language::C => "long long monad(const long long *i) {\n".to_string(),
+        };
+        for (id, node) in nodes.order.iter().enumerate() {
+            let (op, lhs, rhs) = match node.operands() {
+                Some(operands) => operands,
+                None => continue,
+            };
+            let value = match (op, language) {
+                ("==", Language::Rust) => format!("({} == {}) as i64", term(lhs), term(rhs)),
+                _ => format!("{} {} {}", term(lhs), op, term(rhs)),
+            };
+            let declaration = match language {
+                Language::Rust => "let",
+                Language::C => "long long",
+            };
+            out.push_str(&format!("    {} t{} = {};\n", declaration, id, value));
+        }
+        match language {
+            Language::Rust => out.push_str(&format!("    {}\n}}", term(root))),
+            Language::C => out.push_str(&format!("    return {};\n}}", term(root))),
+        }
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::{constant, parse};
+    use super::*;
+
+    /// `(i0 * -1 + 5) == (i0 * -1)`, sharing the product.
+    fn shared() -> Rc<Expression> {
+        let product = Rc::new(Expression::Mul(Rc::new(Expression::Input(0)), constant(-1)));
+        let sum = Rc::new(Expression::Add(product.clone(), constant(5)));
+        Rc::new(Expression::Eql(sum, product))
+    }
+
+    #[test]
+    fn test_display() {
+        assert_eq!(
+            shared().to_string(),
+            "(((i0) * (-1)) + (5)) == ((i0) * (-1))"
+        );
+    }
+
+    #[test]
+    fn test_pretty() {
+        let expected = ["(==", "  (+", "    (* i0 -1)", "    5)", "  (* i0 -1))"].join("\n");
+        assert_eq!(shared().pretty(), expected);
+        assert_eq!(constant(3).pretty(), "3");
+    }
+
+    #[test]
+    fn test_dot() {
+        let expected = [
+            "digraph expression {",
+            "    n0 [label=\"i0\"];",
+            "    n1 [label=\"-1\"];",
+            "    n2 [label=\"*\"];",
+            "    n2 -> n0;",
+            "    n2 -> n1;",
+            "    n3 [label=\"5\"];",
+            "    n4 [label=\"+\"];",
+            "    n4 -> n2;",
+            "    n4 -> n3;",
+            "    n5 [label=\"==\"];",
+            "    n5 -> n4;",
+            "    n5 -> n2;",
+            "}",
+        ]
+        .join("\n");
+        assert_eq!(Expression::dot(&shared()), expected);
+    }
+
+    #[test]
+    fn test_source() {
+        let rust = [
+            "fn monad(i: &[i64; 1]) -> i64 {",
+            "    let t2 = i[0] * -1;",
+            "    let t4 = t2 + 5;",
+            "    let t5 = (t4 == t2) as i64;",
+            "    t5",
+            "}",
+        ]
+        .join("\n");
+        assert_eq!(Expression::source(&shared(), Language::Rust), rust);
+        let c = [
+            "long long monad(const long long *i) {",
+            "    long long t2 = i[0] * -1;",
+            "    long long t4 = t2 + 5;",
+            "    long long t5 = t4 == t2;",
+            "    return t5;",
+            "}",
+        ]
+        .join("\n");
+        assert_eq!(Expression::source(&shared(), Language::C), c);
+    }
+
+    #[test]
+    fn test_source_leaf() {
+        let mut alu = parse("inp z").unwrap();
+        alu.run();
+        let expected = "fn monad(i: &[i64; 1]) -> i64 {\n    i[0]\n}";
+        assert_eq!(Expression::source(&alu.get('z'), Language::Rust), expected);
+    }
+}
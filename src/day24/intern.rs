@@ -0,0 +1,157 @@
+use super::format::Nodes;
+use super::Expression;
+use std::collections::HashMap;
+use std::rc::Rc;
+
+/// A node by its kind and the addresses of its operands. Operands are interned before the nodes
+/// that use them, so the same key means the same subtree.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+enum Key {
+    Constant(i64),
+    Input(u8),
+    Operation(&'static str, *const Expression, *const Expression),
+}
+
+impl Key {
+    fn of(e: &Expression) -> Key {
+        match e {
+            Expression::Constant(c) => Key::Constant(*c),
+            Expression::Input(i) => Key::Input(*i),
+            Expression::Add(lhs, rhs)
+            | Expression::Mul(lhs, rhs)
+            | Expression::Div(lhs, rhs)
+            | Expression::Mod(lhs, rhs)
+            | Expression::Eql(lhs, rhs) => {
+                Key::Operation(e.symbol(), Rc::as_ptr(lhs), Rc::as_ptr(rhs))
+            }
+        }
+    }
+}
+
+/// One shared node for every distinct expression, so that equal expressions are the same `Rc`.
+/// Holding on to every node also keeps the addresses in the keys from being reused.
+#[derive(Debug, Default)]
+pub struct Interner {
+    nodes: HashMap<Key, Rc<Expression>>,
+}
+
+impl Interner {
+    /// The node equal to `e`, which is `e` itself if there wasn't one yet. The operands of `e`
+    /// have to be interned already.
+    pub fn intern(&mut self, e: Rc<Expression>) -> Rc<Expression> {
+        let node = self.nodes.entry(Key::of(&e)).or_insert_with(|| e.clone());
+        debug_assert!(node.same_node(&e), "{} interned as {}", e, node);
+        node.clone()
+    }
+
+    pub fn constant(&mut self, c: i64) -> Rc<Expression> {
+        self.intern(Rc::new(Expression::Constant(c)))
+    }
+
+    pub fn len(&self) -> usize {
+        self.nodes.len()
+    }
+
+    /// How big `root` is written out as a tree, against the nodes it's actually made of.
+    pub fn counts(&self, root: &Rc<Expression>) -> NodeCounts {
+        let nodes = Nodes::new(root);
+        // children come before their parents, so their sizes are already known
+        let mut sizes: Vec<u64> = vec![];
+        for node in &nodes.order {
+            let size = match node.operands() {
+                Some((_, lhs, rhs)) => sizes[nodes.id(lhs)]
+                    .saturating_add(sizes[nodes.id(rhs)])
+                    .saturating_add(1),
+                None => 1,
+            };
+            sizes.push(size);
+        }
+        NodeCounts {
+            tree: sizes.last().copied().unwrap_or(0),
+            distinct: sizes.len(),
+            interned: self.len(),
+        }
+    }
+}
+
+/// The size of an expression written out as a tree, where a shared subtree counts every time it's
+/// used, and the number of distinct nodes it's made of. `interned` counts every node seen by the
+/// interner, from any register or path.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct NodeCounts {
+    pub tree: u64,
+    pub distinct: usize,
+    pub interned: usize,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::{parse, tests::real};
+    use super::*;
+
+    #[test]
+    fn test_intern() {
+        let mut interner = Interner::default();
+        let i0 = interner.intern(Rc::new(Expression::Input(0)));
+        let one = interner.constant(1);
+        let a = interner.intern(Rc::new(Expression::Add(i0.clone(), one.clone())));
+        let also_one = interner.constant(1);
+        let b = interner.intern(Rc::new(Expression::Add(i0.clone(), also_one)));
+        assert!(Rc::ptr_eq(&a, &b));
+        let c = interner.intern(Rc::new(Expression::Add(one, i0)));
+        assert!(!Rc::ptr_eq(&a, &c));
+        assert_eq!(interner.len(), 4);
+    }
+
+    #[test]
+    fn test_node_counts() {
+        let i0 = Rc::new(Expression::Input(0));
+        let square = Rc::new(Expression::Mul(i0.clone(), i0));
+        let sum = Rc::new(Expression::Add(square.clone(), square));
+        assert_eq!(
+            Interner::default().counts(&sum),
+            NodeCounts {
+                tree: 7,
+                distinct: 3,
+                interned: 0
+            }
+        );
+    }
+
+    #[test]
+    fn test_equality() {
+        // equal trees are equal however they're built, but only the same node once interned
+        let tree = || {
+            let i0 = Rc::new(Expression::Input(0));
+            Rc::new(Expression::Add(i0.clone(), i0))
+        };
+        assert_eq!(tree(), tree());
+        assert!(!tree().same_node(&tree()));
+        assert_ne!(tree(), Rc::new(Expression::Mul(tree(), tree())));
+        let mut interner = Interner::default();
+        let mut intern = || {
+            let i0 = interner.intern(Rc::new(Expression::Input(0)));
+            interner.intern(Rc::new(Expression::Add(i0.clone(), i0)))
+        };
+        let (a, b) = (intern(), intern());
+        assert_eq!(a, b);
+        assert!(a.same_node(&b));
+        assert!(Rc::ptr_eq(&a, &b));
+    }
+
+    #[test]
+    fn test_shared_registers() {
+        let init = parse(&real()).unwrap();
+        let mut first = init.clone();
+        first.run();
+        let counts = init.interner.borrow().counts(&first.get('z'));
+        assert!(counts.tree > 10 * counts.distinct as u64, "{:?}", counts);
+
+        // clones share the interner, so running again builds nothing new
+        let interned = init.interner.borrow().len();
+        let mut second = init.clone();
+        second.run();
+        assert!(Rc::ptr_eq(&first.get('z'), &second.get('z')));
+        assert_eq!(init.interner.borrow().len(), interned);
+    }
+}
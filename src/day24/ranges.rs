@@ -0,0 +1,211 @@
+use super::{Expression, DIGITS};
+use std::collections::HashMap;
+use std::rc::Rc;
+
+/// The smallest and largest value of every node of some expressions, with the inputs in `inputs`
+/// fixed and the others anywhere in `DIGITS`. Each node is worked out once, however often it's
+/// shared, and kept for any later expression that uses it.
+#[derive(Debug, Default)]
+pub struct Ranges {
+    inputs: Vec<Option<i64>>,
+    cache: HashMap<*const Expression, (i64, i64)>,
+    /// Keeps the nodes in `cache` alive, so that their addresses can't be reused.
+    roots: Vec<Rc<Expression>>,
+    /// How many times a node's range was worked out rather than looked up.
+    visits: usize,
+}
+
+impl Ranges {
+    pub fn new(inputs: &[Option<i64>]) -> Ranges {
+        Ranges {
+            inputs: inputs.to_vec(),
+            ..Ranges::default()
+        }
+    }
+
+    pub fn of(&mut self, e: &Rc<Expression>) -> (i64, i64) {
+        if let Some(&range) = self.cache.get(&Rc::as_ptr(e)) {
+            return range;
+        }
+        self.roots.push(e.clone());
+        self.node(e)
+    }
+
+    /// The range of `e` from the ranges of its operands, for a node that isn't shared yet and so
+    /// isn't kept.
+    pub fn of_new(&mut self, e: &Expression) -> (i64, i64) {
+        match e.operands() {
+            Some((_, lhs, rhs)) => {
+                let (a, b) = (self.of(lhs), self.of(rhs));
+                combine(e, a, b)
+            }
+            None => self.leaf(e),
+        }
+    }
+
+    pub fn visits(&self) -> usize {
+        self.visits
+    }
+
+    /// Only safe to keep using while `e` is alive, which `of` makes sure of.
+    fn node(&mut self, e: &Expression) -> (i64, i64) {
+        let key = e as *const Expression;
+        if let Some(&range) = self.cache.get(&key) {
+            return range;
+        }
+        self.visits += 1;
+        let range = match e.operands() {
+            Some((_, lhs, rhs)) => {
+                let (a, b) = (self.node(lhs), self.node(rhs));
+                combine(e, a, b)
+            }
+            None => self.leaf(e),
+        };
+        self.cache.insert(key, range);
+        range
+    }
+
+    fn leaf(&self, e: &Expression) -> (i64, i64) {
+        match e {
+            Expression::Constant(c) => (*c, *c),
+            Expression::Input(i) => match self.inputs.get(*i as usize) {
+                Some(Some(v)) => (*v, *v),
+                _ => DIGITS,
+            },
+            _ => unreachable!("only leaves have no operands"),
+        }
+    }
+}
+
+/// The range of the operation `e` on operands in ranges `a` and `b`.
+fn combine(e: &Expression, a: (i64, i64), b: (i64, i64)) -> (i64, i64) {
+    let span = |corners: [i64; 4]| {
+        (
+            *corners.iter().min().unwrap(),
+            *corners.iter().max().unwrap(),
+        )
+    };
+    match e {
+        Expression::Add(..) => (a.0.saturating_add(b.0), a.1.saturating_add(b.1)),
+        Expression::Mul(..) => span([
+            a.0.saturating_mul(b.0),
+            a.0.saturating_mul(b.1),
+            a.1.saturating_mul(b.0),
+            a.1.saturating_mul(b.1),
+        ]),
+        Expression::Div(..) if b.0 <= 0 && b.1 >= 0 => {
+            // dividing by anything but 0 can only shrink the magnitude
+            let m = a.0.saturating_abs().max(a.1.saturating_abs());
+            (-m, m)
+        }
+        Expression::Div(..) => span([
+            a.0.saturating_div(b.0),
+            a.0.saturating_div(b.1),
+            a.1.saturating_div(b.0),
+            a.1.saturating_div(b.1),
+        ]),
+        Expression::Mod(..) if a.0 == a.1 && b.0 == b.1 && b.0 != 0 => {
+            let r = a.0.wrapping_rem(b.0);
+            (r, r)
+        }
+        Expression::Mod(..) => {
+            let m =
+                b.0.saturating_abs()
+                    .max(b.1.saturating_abs())
+                    .saturating_sub(1);
+            if a.0 >= 0 && a.1 < b.0 {
+                a
+            } else if a.0 >= 0 {
+                (0, m.min(a.1))
+            } else {
+                (-m, m)
+            }
+        }
+        Expression::Eql(..) if a.1 < b.0 || b.1 < a.0 => (0, 0),
+        Expression::Eql(..) if a.0 == a.1 && a == b => (1, 1),
+        Expression::Eql(..) => (0, 1),
+        Expression::Constant(_) | Expression::Input(_) => {
+            unreachable!("leaves have no operands to combine")
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::{constant, parse, tests::real};
+    use super::*;
+
+    #[test]
+    fn test_shared_nodes() {
+        // a tree of 2^50 inputs, in 51 nodes
+        let mut e = Rc::new(Expression::Input(0));
+        for _ in 0..50 {
+            e = Rc::new(Expression::Add(e.clone(), e));
+        }
+        let mut ranges = Ranges::default();
+        assert_eq!(ranges.of(&e), (1 << 50, 9 << 50));
+        assert_eq!(ranges.cache.len(), 51);
+        assert_eq!(ranges.roots.len(), 1);
+        // asking again doesn't pin it twice
+        ranges.of(&e);
+        assert_eq!(ranges.roots.len(), 1);
+    }
+
+    #[test]
+    fn test_visits() {
+        // asking for every node as it's built, the way the simplifiers do
+        let mut ranges = Ranges::default();
+        let mut e = Rc::new(Expression::Input(0));
+        for _ in 0..50 {
+            ranges.of(&e);
+            e = Rc::new(Expression::Add(e.clone(), e));
+        }
+        ranges.of(&e);
+        assert_eq!(ranges.visits(), 51);
+
+        // the simplifiers on a whole run share the ALU's ranges, and see each node once
+        let mut alu = parse(&real()).unwrap();
+        alu.run();
+        let visits = alu.ranges.borrow().visits();
+        assert!(visits > 0);
+        assert!(visits <= alu.interner.borrow().len(), "{}", visits);
+        alu.range('z');
+        alu.range('z');
+        assert!(alu.ranges.borrow().visits() <= alu.interner.borrow().len());
+    }
+
+    #[test]
+    fn test_fixed_inputs() {
+        let i0 = Rc::new(Expression::Input(0));
+        let i1 = Rc::new(Expression::Input(1));
+        let e = Rc::new(Expression::Mul(
+            Rc::new(Expression::Add(i0, constant(-5))),
+            i1,
+        ));
+        assert_eq!(Ranges::default().of(&e), (-36, 36));
+        assert_eq!(Ranges::new(&[Some(7)]).of(&e), (2, 18));
+        assert_eq!(Ranges::new(&[Some(2), Some(3)]).of(&e), (-9, -9));
+    }
+
+    #[test]
+    fn test_eql_folds() {
+        // w can't be more than 9, so the first eql is 0 whatever the input
+        let mut alu = parse(&["inp w", "add x 10", "eql x w", "eql x 0"].join("\n")).unwrap();
+        alu.run();
+        assert_eq!(alu.get('x'), constant(1));
+    }
+
+    #[test]
+    fn test_z_bound() {
+        let mut alu = parse(&real()).unwrap();
+        alu.run();
+        let (low, high) = alu.range('z');
+        assert_eq!(low, 0);
+        // seven pushes at most, so nothing near saturating
+        assert!((26_i64.pow(6)..26_i64.pow(7)).contains(&high), "{}", high);
+        // the same nodes are looked up again, not worked out again
+        let cached = alu.ranges.borrow().cache.len();
+        alu.range('z');
+        assert_eq!(alu.ranges.borrow().cache.len(), cached);
+    }
+}
@@ -1,3 +1,8 @@
+use crate::error::ParseError;
+use crate::solution::Solution;
+use crate::util::{Edges, Grid};
+use std::fmt;
+
 #[derive(Clone, Copy, PartialEq)]
 enum Location {
     Empty,
@@ -5,33 +10,24 @@ enum Location {
     SouthFacing,
 }
 
-impl Location {
-    fn to_char(&self) -> &'static str {
-        match self {
+impl fmt::Display for Location {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(match self {
             Location::Empty => ".",
             Location::EastFacing => ">",
             Location::SouthFacing => "v",
-        }
+        })
     }
 }
 
 #[derive(Clone)]
 pub struct SeaCucumbers {
-    map: Vec<Vec<Location>>,
+    map: Grid<Location>,
 }
 
-impl ToString for SeaCucumbers {
-    fn to_string(&self) -> String {
-        self.map
-            .iter()
-            .map(|row| {
-                row.iter()
-                    .map(|loc| loc.to_char())
-                    .collect::<Vec<&str>>()
-                    .join("")
-            })
-            .collect::<Vec<String>>()
-            .join("\n")
+impl fmt::Display for SeaCucumbers {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.map.fmt(f)
     }
 }
 
@@ -41,42 +37,27 @@ impl SeaCucumbers {
         let mut moved = false;
 
         // move east facing sea cucumbers
-        for y in 0..self.map.len() {
-            let row = &self.map[y];
-            let row_len = row.len();
-            for x in 0..row_len {
-                let loc = self.map[y][x];
-
-                let west_x = if x == 0 { row_len - 1 } else { x - 1 };
-                let west = self.map[y][west_x];
-
-                if loc == Location::Empty && west == Location::EastFacing {
-                    moved = true;
-                    half_step_map[y][west_x] = Location::Empty;
-                    half_step_map[y][x] = Location::EastFacing;
-                }
+        for ((x, y), &loc) in self.map.cells() {
+            let west_pos = self.map.step((x, y), (-1, 0), Edges::Wrap).unwrap();
+            let west = self.map[west_pos];
+
+            if loc == Location::Empty && west == Location::EastFacing {
+                moved = true;
+                half_step_map[west_pos] = Location::Empty;
+                half_step_map[(x, y)] = Location::EastFacing;
             }
         }
 
         // move south facing sea cucumbers
         let mut map = half_step_map.clone();
-        for y in 0..half_step_map.len() {
-            let row = &half_step_map[y];
-            for x in 0..row.len() {
-                let loc = half_step_map[y][x];
-
-                let north_y = if y == 0 {
-                    half_step_map.len() - 1
-                } else {
-                    y - 1
-                };
-                let north = half_step_map[north_y][x];
-
-                if loc == Location::Empty && north == Location::SouthFacing {
-                    moved = true;
-                    map[north_y][x] = Location::Empty;
-                    map[y][x] = Location::SouthFacing;
-                }
+        for ((x, y), &loc) in half_step_map.cells() {
+            let north_pos = half_step_map.step((x, y), (0, -1), Edges::Wrap).unwrap();
+            let north = half_step_map[north_pos];
+
+            if loc == Location::Empty && north == Location::SouthFacing {
+                moved = true;
+                map[north_pos] = Location::Empty;
+                map[(x, y)] = Location::SouthFacing;
             }
         }
 
@@ -84,24 +65,14 @@ impl SeaCucumbers {
     }
 }
 
-pub fn parse(input: &str) -> SeaCucumbers {
-    let mut map = vec![];
-
-    for line in input.lines() {
-        let mut row = vec![];
-        for ch in line.chars() {
-            let location = match ch {
-                'v' => Location::SouthFacing,
-                '>' => Location::EastFacing,
-                '.' => Location::Empty,
-                _ => panic!("Invalid location"),
-            };
-            row.push(location);
-        }
-        map.push(row);
-    }
-
-    SeaCucumbers { map }
+pub fn parse(input: &str) -> Result<SeaCucumbers, ParseError> {
+    let map = Grid::parse(25, input, "'v', '>' or '.'", |ch| match ch {
+        'v' => Some(Location::SouthFacing),
+        '>' => Some(Location::EastFacing),
+        '.' => Some(Location::Empty),
+        _ => None,
+    })?;
+    Ok(SeaCucumbers { map })
 }
 
 pub fn part1(input: &SeaCucumbers) -> i32 {
@@ -124,24 +95,36 @@ pub fn part2(_input: &SeaCucumbers) -> i32 {
     0
 }
 
+pub struct Day25;
+
+impl Solution for Day25 {
+    type Input = SeaCucumbers;
+    type Output1 = i32;
+    type Output2 = i32;
+
+    const DAY: i32 = 25;
+    const TITLE: &'static str = "Sea Cucumber";
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        parse(input)
+    }
+
+    fn part1(input: &Self::Input) -> Self::Output1 {
+        part1(input)
+    }
+
+    fn part2(input: &Self::Input) -> Self::Output2 {
+        part2(input)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::util;
 
     fn ex1() -> String {
-        vec![
-            "v...>>.vv>",
-            ".vv>>.vv..",
-            ">>.>v>...v",
-            ">>v>>.>.v.",
-            "v>v.vv.v..",
-            ">.>>..v...",
-            ".vv..>.>v.",
-            "v.v..>>v.v",
-            "....v..v.>",
-        ]
-        .join("\n")
+        util::read_example(25, 1)
     }
 
     fn real() -> String {
@@ -157,21 +140,21 @@ mod tests {
 
     #[test]
     fn test_parse_ex1() {
-        let v = parse(&ex1());
+        let v = parse(&ex1()).unwrap();
         let actual = v.to_string();
         assert_eq!(actual, ex1());
     }
 
     #[test]
     fn test_parse_ex2() {
-        let v = parse(&ex2());
+        let v = parse(&ex2()).unwrap();
         let actual = v.to_string();
         assert_eq!(actual, ex2());
     }
 
     #[test]
     fn test_step_ex2() {
-        let v = parse(&ex2());
+        let v = parse(&ex2()).unwrap();
         let (actual, moved) = v.step();
         let expected = vec![
             "..vv>..", ".......", ">......", "v.....>", ">......", ".......", "....v..",
@@ -182,25 +165,25 @@ mod tests {
     }
     #[test]
     fn test_part1_ex1() {
-        let actual = part1(&parse(&ex1()));
+        let actual = part1(&parse(&ex1()).unwrap());
         assert_eq!(actual, 58);
     }
 
     #[test]
     fn test_part1_real() {
-        let actual = part1(&parse(&real()));
+        let actual = part1(&parse(&real()).unwrap());
         assert_eq!(actual, 557);
     }
 
     #[test]
     fn test_part2_ex1() {
-        let actual = part2(&parse(&ex1()));
+        let actual = part2(&parse(&ex1()).unwrap());
         assert_eq!(actual, 0);
     }
 
     #[test]
     fn test_part2_real() {
-        let actual = part2(&parse(&real()));
+        let actual = part2(&parse(&real()).unwrap());
         assert_eq!(actual, 0);
     }
 }
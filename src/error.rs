@@ -0,0 +1,126 @@
+use std::fmt;
+use std::str::FromStr;
+
+/// Why (and where) a puzzle input could not be parsed. Lines and columns are 1-based.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ParseError {
+    pub day: i32,
+    pub line: usize,
+    pub column: usize,
+    pub expected: String,
+}
+
+impl ParseError {
+    pub fn new(day: i32, line: usize, column: usize, expected: &str) -> ParseError {
+        ParseError {
+            day,
+            line,
+            column,
+            expected: expected.to_string(),
+        }
+    }
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "day {}, line {}, column {}: expected {}",
+            self.day, self.line, self.column, self.expected
+        )
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+/// A line of puzzle input, which knows enough about where it came from to build a [`ParseError`].
+#[derive(Clone, Copy, Debug)]
+pub struct InputLine<'a> {
+    pub day: i32,
+    pub number: usize,
+    pub text: &'a str,
+}
+
+pub fn input_lines(day: i32, input: &str) -> impl Iterator<Item = InputLine<'_>> {
+    input.lines().enumerate().map(move |(idx, text)| InputLine {
+        day,
+        number: idx + 1,
+        text,
+    })
+}
+
+impl<'a> InputLine<'a> {
+    /// An error at the start of the line.
+    pub fn error(&self, expected: &str) -> ParseError {
+        ParseError::new(self.day, self.number, 1, expected)
+    }
+
+    /// An error at `part`, which should be a slice of this line's text.
+    pub fn error_at(&self, part: &str, expected: &str) -> ParseError {
+        let start = self.text.as_ptr() as usize;
+        let offset = (part.as_ptr() as usize).wrapping_sub(start);
+        let column = if offset <= self.text.len() {
+            offset + 1
+        } else {
+            1
+        };
+        ParseError::new(self.day, self.number, column, expected)
+    }
+
+    /// An error just past the end of the line, for when something is missing.
+    pub fn error_at_end(&self, expected: &str) -> ParseError {
+        ParseError::new(self.day, self.number, self.text.len() + 1, expected)
+    }
+
+    pub fn parse<T: FromStr>(&self, part: &str, expected: &str) -> Result<T, ParseError> {
+        part.parse().map_err(|_| self.error_at(part, expected))
+    }
+
+    pub fn split_once(&self, delimiter: &str) -> Result<(&'a str, &'a str), ParseError> {
+        self.text
+            .split_once(delimiter)
+            .ok_or_else(|| self.error_at_end(&format!("'{}'", delimiter)))
+    }
+
+    /// Each character of the line, along with its 1-based column.
+    pub fn chars(&self) -> impl Iterator<Item = (usize, char)> + 'a {
+        self.text.char_indices().map(|(idx, ch)| (idx + 1, ch))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_display() {
+        let err = ParseError::new(22, 3, 5, "a number");
+        assert_eq!(
+            err.to_string(),
+            "day 22, line 3, column 5: expected a number"
+        );
+    }
+
+    #[test]
+    fn test_input_lines() {
+        let lines: Vec<_> = input_lines(4, "a\nb").map(|l| (l.number, l.text)).collect();
+        assert_eq!(lines, vec![(1, "a"), (2, "b")]);
+    }
+
+    #[test]
+    fn test_error_at() {
+        let line = input_lines(5, "0,9 -> 5,x").next().unwrap();
+        let (_, end) = line.split_once(" -> ").unwrap();
+        let (_, y) = end.split_once(',').unwrap();
+        assert_eq!(
+            line.parse::<i32>(y, "a number"),
+            Err(ParseError::new(5, 1, 10, "a number"))
+        );
+    }
+
+    #[test]
+    fn test_split_once_missing() {
+        let line = input_lines(12, "start\nA-end").next().unwrap();
+        assert_eq!(line.split_once("-"), Err(ParseError::new(12, 1, 6, "'-'")));
+    }
+}
@@ -0,0 +1,126 @@
+//! Checks every registered day against the examples from the puzzle descriptions.
+//!
+//! Each example is a pair of files in `examples/`: the input in `dayNN-K.txt`, and the expected
+//! answers in `dayNN-K.toml` as `part1 = ...` and `part2 = ...` lines, in the same format as
+//! `answers.toml`. Parts that aren't listed aren't checked. New examples are picked up without
+//! any code changes.
+
+use crate::answers::{self, Answers, Verdict};
+use crate::cli::Part;
+use crate::solution::{self, Runner};
+use crate::util;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+#[derive(Debug)]
+struct Example {
+    day: i32,
+    input: PathBuf,
+    answers: PathBuf,
+}
+
+/// Parses `dayNN-K.txt` into the day.
+fn example_day(name: &str) -> Option<i32> {
+    let (day, n) = name
+        .strip_prefix("day")?
+        .strip_suffix(".txt")?
+        .split_once('-')?;
+    n.parse::<usize>().ok()?;
+    day.parse().ok()
+}
+
+fn discover(dir: &Path) -> Vec<Example> {
+    let mut r: Vec<Example> = fs::read_dir(dir)
+        .unwrap_or_else(|err| panic!("Could not read {}: {}", dir.display(), err))
+        .flatten()
+        .filter_map(|entry| {
+            let day = example_day(&entry.file_name().to_string_lossy())?;
+            let input = entry.path();
+            Some(Example {
+                day,
+                answers: input.with_extension("toml"),
+                input,
+            })
+        })
+        .collect();
+    r.sort_by(|a, b| a.input.cmp(&b.input));
+    r
+}
+
+/// Runs one example, returning a description of each problem.
+fn check(runner: &dyn Runner, example: &Example) -> Vec<String> {
+    let name = example.input.display();
+    let answers = fs::read_to_string(&example.answers)
+        .map_err(|err| format!("{}: {}", example.answers.display(), err))
+        .and_then(|answers| {
+            // the table is implied by the file name
+            Answers::parse(&format!("[day{:02}]\n{}", example.day, answers))
+                .map_err(|err| format!("{}: {}", example.answers.display(), err))
+        });
+    let answers = match answers {
+        Ok(answers) => answers,
+        Err(err) => return vec![err],
+    };
+
+    // only run the parts with answers, since the others may be unsolved (and slow)
+    let part = match (answers.get(example.day, 1), answers.get(example.day, 2)) {
+        (Some(_), Some(_)) => Part::Both,
+        (Some(_), None) => Part::One,
+        (None, Some(_)) => Part::Two,
+        (None, None) => return vec![],
+    };
+    let report = match util::read_input_file(&example.input)
+        .and_then(|input| runner.run(&input, part).map_err(|err| err.to_string()))
+    {
+        Ok(report) => report,
+        Err(err) => return vec![format!("{}: {}", name, err)],
+    };
+
+    answers::verify(&answers, &report)
+        .into_iter()
+        .filter(|(_, verdict)| !matches!(verdict, Verdict::Pass))
+        .map(|(part, verdict)| {
+            let verdict = answers::format_verdict(example.day, part, &verdict);
+            format!("{}: {}", name, verdict.replace(",\t", ", "))
+        })
+        .collect()
+}
+
+#[test]
+fn test_examples() {
+    let examples = discover(Path::new(util::EXAMPLE_DIR));
+    let mut failures = vec![];
+    for runner in solution::registry() {
+        for example in examples.iter().filter(|e| e.day == runner.day()) {
+            failures.extend(check(runner, example));
+        }
+    }
+    assert!(failures.is_empty(), "\n{}\n", failures.join("\n"));
+}
+
+#[test]
+fn test_examples_are_complete() {
+    let examples = discover(Path::new(util::EXAMPLE_DIR));
+    assert!(!examples.is_empty());
+    for example in examples {
+        assert!(
+            solution::find(example.day).is_some(),
+            "{} is for an unknown day",
+            example.input.display()
+        );
+        assert!(
+            example.answers.is_file(),
+            "{} has no answers",
+            example.input.display()
+        );
+    }
+}
+
+#[test]
+fn test_example_day() {
+    assert_eq!(example_day("day04-1.txt"), Some(4));
+    assert_eq!(example_day("day16-12.txt"), Some(16));
+    assert_eq!(example_day("day04-1.toml"), None);
+    assert_eq!(example_day("day04.txt"), None);
+    assert_eq!(example_day("day04-x.txt"), None);
+}
@@ -0,0 +1,132 @@
+use crate::cli::Part;
+use crate::error::ParseError;
+use crate::solution::{Report, Runner};
+use std::any::Any;
+use std::fmt;
+use std::panic::{self, AssertUnwindSafe};
+use std::sync::mpsc;
+use std::thread;
+use std::time::Duration;
+
+/// Why a day produced no report at all.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Failure {
+    Panicked(String),
+    TimedOut(Duration),
+}
+
+impl fmt::Display for Failure {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Failure::Panicked(msg) => write!(f, "FAILED: {}", msg),
+            Failure::TimedOut(timeout) => {
+                write!(f, "TIMEOUT after {:.3} s", timeout.as_secs_f64())
+            }
+        }
+    }
+}
+
+fn panic_message(payload: Box<dyn Any + Send>) -> String {
+    if let Some(msg) = payload.downcast_ref::<&str>() {
+        msg.to_string()
+    } else if let Some(msg) = payload.downcast_ref::<String>() {
+        msg.clone()
+    } else {
+        "panicked".to_string()
+    }
+}
+
+/// Runs a day on its own thread, so that a panic or a solver that never finishes doesn't take
+/// down the other days. A day that times out is abandoned, and keeps running in the background
+/// until the process exits.
+pub fn run(
+    runner: &'static dyn Runner,
+    input: String,
+    part: Part,
+    timeout: Option<Duration>,
+) -> Result<Result<Report, ParseError>, Failure> {
+    let (tx, rx) = mpsc::channel();
+    thread::Builder::new()
+        .name(format!("day{:02}", runner.day()))
+        .spawn(move || {
+            let result = panic::catch_unwind(AssertUnwindSafe(|| runner.run(&input, part)));
+            // the receiver is gone if we timed out, which is fine
+            let _ = tx.send(result.map_err(panic_message));
+        })
+        .map_err(|err| Failure::Panicked(format!("could not start thread: {}", err)))?;
+
+    let result = match timeout {
+        Some(timeout) => rx
+            .recv_timeout(timeout)
+            .map_err(|_| Failure::TimedOut(timeout))?,
+        None => rx
+            .recv()
+            .map_err(|_| Failure::Panicked("thread exited".to_string()))?,
+    };
+    result.map_err(Failure::Panicked)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::solution::Solution;
+
+    struct Misbehaving;
+
+    impl Solution for Misbehaving {
+        type Input = u64;
+        type Output1 = u64;
+        type Output2 = u64;
+
+        const DAY: i32 = 99;
+        const TITLE: &'static str = "Misbehaving";
+
+        fn parse(input: &str) -> Result<u64, ParseError> {
+            input
+                .parse()
+                .map_err(|_| ParseError::new(99, 1, 1, "a number"))
+        }
+
+        fn part1(input: &u64) -> u64 {
+            thread::sleep(Duration::from_millis(*input));
+            *input
+        }
+
+        fn part2(_: &u64) -> u64 {
+            panic!("Ran out of folds")
+        }
+    }
+
+    #[test]
+    fn test_run() {
+        let report = run(&Misbehaving, "5".to_string(), Part::One, None);
+        let report = report.unwrap().unwrap();
+        assert_eq!(report.part1.map(|a| a.value), Some("5".to_string()));
+    }
+
+    #[test]
+    fn test_run_invalid_input() {
+        let actual = run(&Misbehaving, "x".to_string(), Part::One, None);
+        assert_eq!(actual, Ok(Err(ParseError::new(99, 1, 1, "a number"))));
+    }
+
+    #[test]
+    fn test_run_panic() {
+        let actual = run(&Misbehaving, "0".to_string(), Part::Both, None);
+        assert_eq!(
+            actual,
+            Err(Failure::Panicked("Ran out of folds".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_run_timeout() {
+        let timeout = Duration::from_millis(10);
+        let actual = run(&Misbehaving, "5000".to_string(), Part::One, Some(timeout));
+        assert_eq!(actual, Err(Failure::TimedOut(timeout)));
+        assert_eq!(
+            Failure::TimedOut(timeout).to_string(),
+            "TIMEOUT after 0.010 s"
+        );
+    }
+}
@@ -1,3 +1,7 @@
+mod answers;
+mod batch;
+mod bench;
+mod cli;
 mod day01;
 mod day02;
 mod day03;
@@ -23,60 +27,288 @@ mod day22;
 mod day23;
 mod day24;
 mod day25;
+mod error;
+#[cfg(test)]
+mod examples;
+mod isolate;
+mod output;
+mod parallel;
+mod scaffold;
+mod solution;
 mod util;
 
-fn ex2<IN, OUT1, OUT2, FP, F1, F2>(day: i32, parse: FP, p1: F1, p2: F2)
-where
-    OUT1: std::fmt::Display,
-    OUT2: std::fmt::Display,
-    FP: Fn(&str) -> IN,
-    F1: Fn(&IN) -> OUT1,
-    F2: Fn(&IN) -> OUT2,
-{
-    let input = util::read_input(day);
-    let n0 = std::time::Instant::now();
-    let input = parse(&input);
-
-    let n1 = std::time::Instant::now();
-    let r1 = p1(&input);
-    let t1 = n1.elapsed().as_secs_f64() * 1000.;
-
-    let n2 = std::time::Instant::now();
-    let r2 = p2(&input);
-    let t2 = n2.elapsed().as_secs_f64() * 1000.;
-
-    let t_total = n0.elapsed().as_secs_f64() * 1000.;
-
-    println!("Day {},\tpart 1: {} ({:.3} ms)", day, r1, t1);
-    println!("       \tpart 2: {} ({:.3} ms)", r2, t2);
-    println!("       \ttotal: {:.3} ms", t_total);
+use answers::Answers;
+use cli::Options;
+use isolate::Failure;
+use solution::{Report, Runner};
+use std::time::Instant;
+
+fn input_for(day: i32, options: &Options) -> Result<String, String> {
+    match options.input_for(day) {
+        Some(path) => util::read_input_file(path),
+        None => {
+            let dir = options.input_dir.clone().unwrap_or_else(util::input_dir);
+            util::read_input_file(util::input_path(&dir, day))
+        }
+    }
+}
+
+/// Why a day has no report.
+enum DayError {
+    /// Printed to stderr, like a missing input or a parse error.
+    Error(String),
+    /// Printed as a FAILED or TIMEOUT row, in place of the day's answers.
+    Failed(Failure),
+}
+
+fn solve(runner: &'static dyn Runner, options: &Options) -> Result<Report, DayError> {
+    let input = input_for(runner.day(), options).map_err(DayError::Error)?;
+    match isolate::run(runner, input, options.part, options.timeout) {
+        Ok(Ok(report)) => Ok(report),
+        Ok(Err(err)) => Err(DayError::Error(format!("Could not parse input: {}", err))),
+        Err(failure) => Err(DayError::Failed(failure)),
+    }
+}
+
+/// Runs the days on `options.jobs` threads, printing the reports in day order. Returns true if
+/// nothing failed.
+fn run_days(options: &Options, runners: &[&'static dyn Runner]) -> bool {
+    if let Some(header) = options.format.header() {
+        println!("{}", header);
+    }
+
+    let start = Instant::now();
+    let mut ok = true;
+    // the CPU time of every day, which is about what they'd take one after another
+    let mut cpu_ms = Some(0.);
+    parallel::run_ordered(
+        runners,
+        options.jobs,
+        |&runner| (runner.day(), solve(runner, options)),
+        |(day, result)| match result {
+            Ok(report) => {
+                cpu_ms = cpu_ms.zip(report.cpu_ms).map(|(sum, ms)| sum + ms);
+                print!("{}", options.format.format(&report));
+            }
+            Err(DayError::Error(err)) => {
+                eprintln!("{}\n", err);
+                ok = false;
+            }
+            Err(DayError::Failed(failure)) => {
+                print!("{}", options.format.failure(day, &failure));
+                ok = false;
+            }
+        },
+    );
+
+    if options.jobs > 1 {
+        let elapsed_ms = start.elapsed().as_secs_f64() * 1000.;
+        match cpu_ms {
+            Some(cpu_ms) => eprintln!(
+                "{} jobs: {:.3} ms elapsed, {:.3} ms CPU time ({:.1}x speedup)",
+                options.jobs,
+                elapsed_ms,
+                cpu_ms,
+                cpu_ms / elapsed_ms
+            ),
+            None => eprintln!("{} jobs: {:.3} ms elapsed", options.jobs, elapsed_ms),
+        }
+    }
+    ok
+}
+
+/// Runs the days, checking the results against the answers file. Returns true if nothing failed.
+fn verify(options: &Options, runners: &[&'static dyn Runner]) -> bool {
+    let answers = match Answers::load(&options.answers) {
+        Ok(answers) => answers,
+        Err(err) => {
+            eprintln!("{}", err);
+            return false;
+        }
+    };
+
+    let (mut passed, mut failed, mut missing) = (0, 0, 0);
+    for &runner in runners {
+        let report = match solve(runner, options) {
+            Ok(report) => report,
+            Err(DayError::Error(err)) => {
+                eprintln!("{}\n", err);
+                failed += 1;
+                continue;
+            }
+            Err(DayError::Failed(failure)) => {
+                println!("Day {},\t{}", runner.day(), failure);
+                failed += 1;
+                continue;
+            }
+        };
+
+        for (part, verdict) in answers::verify(&answers, &report) {
+            println!("{}", answers::format_verdict(report.day, part, &verdict));
+            match verdict {
+                answers::Verdict::Pass => passed += 1,
+                answers::Verdict::Fail { .. } => failed += 1,
+                answers::Verdict::Missing { .. } => missing += 1,
+            }
+        }
+    }
+
     println!();
+    println!("{} passed, {} failed, {} missing", passed, failed, missing);
+    failed == 0
+}
+
+/// Benchmarks the days, optionally comparing against or saving a baseline. Returns true if
+/// nothing failed.
+fn bench(options: &Options, runners: &[&dyn Runner], iterations: usize) -> bool {
+    let baseline = match options.baseline.as_deref().map(bench::Baseline::load) {
+        Some(Ok(baseline)) => Some(baseline),
+        Some(Err(err)) => {
+            eprintln!("{}", err);
+            return false;
+        }
+        None => None,
+    };
+
+    let mut ok = true;
+    let mut results = vec![];
+    for &runner in runners {
+        let input = match input_for(runner.day(), options) {
+            Ok(input) => input,
+            Err(err) => {
+                eprintln!("{}", err);
+                ok = false;
+                continue;
+            }
+        };
+        match bench::bench(runner, &input, options.part, options.warmup, iterations) {
+            Ok(result) => {
+                print!("{}", bench::format_result(&result, baseline.as_ref()));
+                results.push(result);
+            }
+            Err(err) => {
+                eprintln!("Could not parse input: {}", err);
+                eprintln!();
+                ok = false;
+            }
+        }
+    }
+
+    if let Some(path) = &options.save_baseline {
+        if let Err(err) = std::fs::write(path, bench::format_baseline(&results)) {
+            eprintln!("Could not save baseline to {}: {}", path, err);
+            ok = false;
+        }
+    }
+    ok
+}
+
+/// Runs the (single) selected day against each input in the batch. Returns true if nothing failed.
+fn batch(spec: &str, options: &Options, runners: &[&'static dyn Runner]) -> bool {
+    let paths = match batch::expand(spec) {
+        Ok(paths) => paths,
+        Err(err) => {
+            eprintln!("{}", err);
+            return false;
+        }
+    };
+
+    let mut ok = true;
+    for &runner in runners {
+        let rows = batch::run(runner, &paths, options.part, options.timeout);
+        println!("Day {}: {}", runner.day(), runner.title());
+        print!("{}", batch::format_table(&rows));
+        ok &= rows.iter().all(|row| row.report.is_ok());
+    }
+    ok
+}
+
+/// Prints the (single) selected day's `what` view of its input. Returns true if it has one.
+fn explain(what: &str, options: &Options, runners: &[&dyn Runner]) -> bool {
+    let mut ok = true;
+    for &runner in runners {
+        match input_for(runner.day(), options).and_then(|input| runner.explain(&input, what)) {
+            Ok(text) => println!("{}", text),
+            Err(err) => {
+                eprintln!("{}", err);
+                ok = false;
+            }
+        }
+    }
+    ok
+}
+
+fn scaffold<I: Iterator<Item = String>>(args: I) {
+    let options = match cli::parse_scaffold_args(args) {
+        Ok(Some(options)) => options,
+        Ok(None) => {
+            println!("{}", cli::SCAFFOLD_USAGE);
+            return;
+        }
+        Err(err) => {
+            eprintln!("{}\n\n{}", err, cli::SCAFFOLD_USAGE);
+            std::process::exit(2);
+        }
+    };
+
+    match scaffold::scaffold(&options) {
+        Ok(log) => log.iter().for_each(|line| println!("{}", line)),
+        Err(err) => {
+            eprintln!("{}", err);
+            std::process::exit(1);
+        }
+    }
 }
 
 fn main() {
-    ex2(1, day01::parse, day01::part1, day01::part2);
-    ex2(2, day02::parse, day02::part1, day02::part2);
-    ex2(3, day03::parse, day03::part1, day03::part2);
-    ex2(4, day04::parse, day04::part1, day04::part2);
-    ex2(5, day05::parse, day05::part1, day05::part2);
-    ex2(6, day06::parse, day06::part1, day06::part2);
-    ex2(7, day07::parse, day07::part1, day07::part2);
-    ex2(8, day08::parse, day08::part1, day08::part2);
-    ex2(9, day09::parse, day09::part1, day09::part2);
-    ex2(10, day10::parse, day10::part1, day10::part2);
-    ex2(11, day11::parse, day11::part1, day11::part2);
-    ex2(12, day12::parse, day12::part1, day12::part2);
-    ex2(13, day13::parse, day13::part1, day13::part2);
-    ex2(14, day14::parse, day14::part1, day14::part2);
-    ex2(15, day15::parse, day15::part1, day15::part2);
-    ex2(16, day16::parse, day16::part1, day16::part2);
-    ex2(17, day17::parse, day17::part1, day17::part2);
-    ex2(18, day18::parse, day18::part1, day18::part2);
-    ex2(19, day19::parse, day19::part1, day19::part2);
-    ex2(20, day20::parse, day20::part1, day20::part2);
-    ex2(21, day21::parse, day21::part1, day21::part2);
-    ex2(22, day22::parse, day22::part1, day22::part2);
-    ex2(23, day23::parse, day23::part1, day23::part2);
-    ex2(24, day24::parse, day24::part1, day24::part2);
-    ex2(25, day25::parse, day25::part1, day25::part2);
+    let mut args = std::env::args().skip(1).peekable();
+    if args.peek().map(|arg| arg.as_str()) == Some("scaffold") {
+        args.next();
+        scaffold(args);
+        return;
+    }
+
+    let options = match cli::parse_args(args) {
+        Ok(options) => options,
+        Err(err) => {
+            eprintln!("{}\n\n{}", err, cli::USAGE);
+            std::process::exit(2);
+        }
+    };
+
+    if options.help {
+        println!("{}", cli::USAGE);
+        return;
+    }
+
+    if options.list {
+        for runner in solution::registry() {
+            println!("Day {:2}: {}", runner.day(), runner.title());
+        }
+        return;
+    }
+
+    let mut runners = vec![];
+    for &day in options.days.iter() {
+        match solution::find(day) {
+            Some(runner) => runners.push(runner),
+            None => eprintln!("Day {} is not implemented", day),
+        }
+    }
+
+    let ok = if options.verify {
+        verify(&options, &runners)
+    } else if let Some(spec) = &options.batch {
+        batch(spec, &options, &runners)
+    } else if let Some(what) = &options.explain {
+        explain(what, &options, &runners)
+    } else if let Some(iterations) = options.bench {
+        bench(&options, &runners, iterations)
+    } else {
+        run_days(&options, &runners)
+    };
+
+    if !ok {
+        std::process::exit(1);
+    }
 }
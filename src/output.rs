@@ -0,0 +1,208 @@
+use crate::isolate::Failure;
+use crate::solution::{Answer, Report};
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Format {
+    Text,
+    Json,
+    Csv,
+}
+
+impl Format {
+    pub fn parse(s: &str) -> Result<Format, String> {
+        match s {
+            "text" => Ok(Format::Text),
+            "json" => Ok(Format::Json),
+            "csv" => Ok(Format::Csv),
+            _ => Err(format!(
+                "Invalid format '{}', expected text, json or csv",
+                s
+            )),
+        }
+    }
+
+    /// Printed once, before any of the reports.
+    pub fn header(&self) -> Option<&'static str> {
+        match self {
+            Format::Csv => Some("day,part,answer,parse_ms,solve_ms,total_ms"),
+            _ => None,
+        }
+    }
+
+    pub fn format(&self, report: &Report) -> String {
+        match self {
+            Format::Text => text(report),
+            Format::Json => rows(report)
+                .map(|(part, answer)| json_row(report, part, answer))
+                .collect(),
+            Format::Csv => rows(report)
+                .map(|(part, answer)| csv_row(report, part, answer))
+                .collect(),
+        }
+    }
+
+    /// A row for a day that panicked or timed out, in place of its answers.
+    pub fn failure(&self, day: i32, failure: &Failure) -> String {
+        let failure = failure.to_string();
+        match self {
+            Format::Text => format!("Day {},\t{}\n\n", day, failure),
+            Format::Json => format!("{{\"day\":{},\"error\":{}}}\n", day, json_string(&failure)),
+            Format::Csv => format!("{},,{},,,\n", day, csv_field(&failure)),
+        }
+    }
+}
+
+fn rows(report: &Report) -> impl Iterator<Item = (u8, &Answer)> {
+    [(1, &report.part1), (2, &report.part2)]
+        .into_iter()
+        .filter_map(|(part, answer)| answer.as_ref().map(|answer| (part, answer)))
+}
+
+fn text(report: &Report) -> String {
+    let mut r = String::new();
+    let mut label = format!("Day {},", report.day);
+    for (part, answer) in rows(report) {
+        r += &format!(
+            "{}\tpart {}: {} ({:.3} ms)\n",
+            label, part, answer.value, answer.time_ms
+        );
+        label = " ".repeat(7);
+    }
+    r += &format!("       \ttotal: {:.3} ms\n\n", report.total_ms);
+    r
+}
+
+fn json_string(s: &str) -> String {
+    let mut r = String::from("\"");
+    for ch in s.chars() {
+        match ch {
+            '"' => r += "\\\"",
+            '\\' => r += "\\\\",
+            '\n' => r += "\\n",
+            '\r' => r += "\\r",
+            '\t' => r += "\\t",
+            ch if (ch as u32) < 0x20 => r += &format!("\\u{:04x}", ch as u32),
+            ch => r.push(ch),
+        }
+    }
+    r.push('"');
+    r
+}
+
+fn json_row(report: &Report, part: u8, answer: &Answer) -> String {
+    format!(
+        "{{\"day\":{},\"part\":{},\"answer\":{},\"parse_ms\":{:.3},\"solve_ms\":{:.3},\"total_ms\":{:.3}}}\n",
+        report.day,
+        part,
+        json_string(&answer.value),
+        report.parse_ms,
+        answer.time_ms,
+        report.total_ms
+    )
+}
+
+fn csv_field(s: &str) -> String {
+    if s.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", s.replace('"', "\"\""))
+    } else {
+        s.to_string()
+    }
+}
+
+fn csv_row(report: &Report, part: u8, answer: &Answer) -> String {
+    format!(
+        "{},{},{},{:.3},{:.3},{:.3}\n",
+        report.day,
+        part,
+        csv_field(&answer.value),
+        report.parse_ms,
+        answer.time_ms,
+        report.total_ms
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn report() -> Report {
+        Report {
+            day: 13,
+            parse_ms: 0.25,
+            part1: Some(Answer {
+                value: "942".to_string(),
+                time_ms: 1.5,
+            }),
+            part2: Some(Answer {
+                value: "\n# \"#\"\n".to_string(),
+                time_ms: 2.,
+            }),
+            total_ms: 4.,
+            cpu_ms: None,
+        }
+    }
+
+    #[test]
+    fn test_text() {
+        let actual = Format::Text.format(&report());
+        assert_eq!(
+            actual,
+            [
+                "Day 13,\tpart 1: 942 (1.500 ms)",
+                "       \tpart 2: \n# \"#\"\n (2.000 ms)",
+                "       \ttotal: 4.000 ms",
+                "",
+                "",
+            ]
+            .join("\n")
+        );
+    }
+
+    #[test]
+    fn test_json() {
+        let actual = Format::Json.format(&report());
+        assert_eq!(
+            actual,
+            [
+                r#"{"day":13,"part":1,"answer":"942","parse_ms":0.250,"solve_ms":1.500,"total_ms":4.000}"#,
+                r##"{"day":13,"part":2,"answer":"\n# \"#\"\n","parse_ms":0.250,"solve_ms":2.000,"total_ms":4.000}"##,
+                "",
+            ]
+            .join("\n")
+        );
+    }
+
+    #[test]
+    fn test_csv() {
+        let actual = Format::Csv.format(&report());
+        assert_eq!(
+            actual,
+            "13,1,942,0.250,1.500,4.000\n13,2,\"\n# \"\"#\"\"\n\",0.250,2.000,4.000\n"
+        );
+    }
+
+    #[test]
+    fn test_failure() {
+        let failure = Failure::Panicked("Ran out of folds".to_string());
+        assert_eq!(
+            Format::Text.failure(13, &failure),
+            "Day 13,\tFAILED: Ran out of folds\n\n"
+        );
+        assert_eq!(
+            Format::Json.failure(13, &failure),
+            "{\"day\":13,\"error\":\"FAILED: Ran out of folds\"}\n"
+        );
+        assert_eq!(
+            Format::Csv.failure(13, &failure),
+            "13,,FAILED: Ran out of folds,,,\n"
+        );
+    }
+
+    #[test]
+    fn test_single_part() {
+        let mut report = report();
+        report.part2 = None;
+        let actual = Format::Csv.format(&report);
+        assert_eq!(actual, "13,1,942,0.250,1.500,4.000\n");
+    }
+}
@@ -0,0 +1,84 @@
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::mpsc;
+use std::thread;
+
+pub fn available_jobs() -> usize {
+    thread::available_parallelism().map_or(1, |n| n.get())
+}
+
+/// Applies `f` to each item on up to `jobs` threads. `done` is called with the results in the
+/// same order as the items, each as soon as it and every result before it are ready.
+pub fn run_ordered<T, R, F, D>(items: &[T], jobs: usize, f: F, mut done: D)
+where
+    T: Sync,
+    R: Send,
+    F: Fn(&T) -> R + Sync,
+    D: FnMut(R),
+{
+    let next = AtomicUsize::new(0);
+    let (tx, rx) = mpsc::channel();
+
+    thread::scope(|scope| {
+        for _ in 0..jobs.clamp(1, items.len().max(1)) {
+            let tx = tx.clone();
+            let (next, f) = (&next, &f);
+            scope.spawn(move || loop {
+                let idx = next.fetch_add(1, Ordering::Relaxed);
+                if idx >= items.len() {
+                    break;
+                }
+                if tx.send((idx, f(&items[idx]))).is_err() {
+                    break;
+                }
+            });
+        }
+        drop(tx);
+
+        let mut pending: Vec<Option<R>> = items.iter().map(|_| None).collect();
+        let mut printed = 0;
+        for (idx, result) in rx {
+            pending[idx] = Some(result);
+            while let Some(result) = pending.get_mut(printed).and_then(|r| r.take()) {
+                done(result);
+                printed += 1;
+            }
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[test]
+    fn test_run_ordered() {
+        let items: Vec<u64> = (0..8).collect();
+        for jobs in [1, 3, 8, 20] {
+            let mut actual = vec![];
+            run_ordered(
+                &items,
+                jobs,
+                |&i| {
+                    // finish out of order
+                    thread::sleep(Duration::from_millis(8 - i));
+                    i * 10
+                },
+                |r| actual.push(r),
+            );
+            assert_eq!(
+                actual,
+                vec![0, 10, 20, 30, 40, 50, 60, 70],
+                "jobs = {}",
+                jobs
+            );
+        }
+    }
+
+    #[test]
+    fn test_run_ordered_empty() {
+        let mut called = false;
+        run_ordered(&[] as &[i32], 4, |&i| i, |_| called = true);
+        assert!(!called);
+    }
+}
@@ -0,0 +1,397 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+pub const DEFAULT_BASE_URL: &str = "https://adventofcode.com";
+
+/// Environment variable overriding [`DEFAULT_BASE_URL`], e.g. to point at a mock server.
+pub const BASE_URL_VAR: &str = "AOC_BASE_URL";
+
+#[derive(Debug, Eq, PartialEq)]
+pub struct Scaffold {
+    pub day: i32,
+    pub year: i32,
+    pub title: String,
+    pub base_url: String,
+    /// A curl cookie file holding the adventofcode.com session cookie.
+    pub cookies: String,
+    /// The directory holding `src/`, normally the current directory.
+    pub root: PathBuf,
+    pub fetch: bool,
+}
+
+const TEMPLATE: &str = r#"use crate::error::{input_lines, ParseError};
+use crate::solution::Solution;
+
+pub fn parse(input: &str) -> Result<Vec<String>, ParseError> {
+    Ok(input_lines({day}, input)
+        .map(|line| line.text.to_string())
+        .collect())
+}
+
+pub fn part1(_input: &Vec<String>) -> i32 {
+    0
+}
+
+pub fn part2(_input: &Vec<String>) -> i32 {
+    0
+}
+
+pub struct Day{dd};
+
+impl Solution for Day{dd} {
+    type Input = Vec<String>;
+    type Output1 = i32;
+    type Output2 = i32;
+
+    const DAY: i32 = {day};
+    const TITLE: &'static str = "{title}";
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        parse(input)
+    }
+
+    fn part1(input: &Self::Input) -> Self::Output1 {
+        part1(input)
+    }
+
+    fn part2(input: &Self::Input) -> Self::Output2 {
+        part2(input)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::util;
+
+    fn real() -> String {
+        util::read_input({day})
+    }
+
+    #[test]
+    fn test_part1_real() {
+        let actual = part1(&parse(&real()).unwrap());
+        assert_eq!(actual, 0);
+    }
+
+    #[test]
+    fn test_part2_real() {
+        let actual = part2(&parse(&real()).unwrap());
+        assert_eq!(actual, 0);
+    }
+}
+"#;
+
+const EXAMPLE_ANSWERS: &str = "\
+# The answers to the example in the puzzle description, checked by `cargo test`
+# part1 = 0
+# part2 = 0
+";
+
+pub fn template(day: i32, title: &str) -> String {
+    TEMPLATE
+        .replace("{day}", &day.to_string())
+        .replace("{dd}", &format!("{:02}", day))
+        .replace("{title}", &title.escape_default().to_string())
+}
+
+/// Inserts `line` into the sorted run of lines that start with `prefix`.
+fn insert_sorted(source: &str, prefix: &str, line: &str) -> Result<String, String> {
+    let lines: Vec<&str> = source.lines().collect();
+    let first = lines
+        .iter()
+        .position(|l| l.starts_with(prefix))
+        .ok_or_else(|| format!("expected a line starting with '{}'", prefix.trim()))?;
+    let mut end = first;
+    while end < lines.len() && lines[end].starts_with(prefix) {
+        if lines[end] == line {
+            return Err(format!("'{}' is already there", line.trim()));
+        }
+        end += 1;
+    }
+    let at = (first..end).find(|&i| lines[i] > line).unwrap_or(end);
+
+    let mut r: Vec<&str> = lines[..at].to_vec();
+    r.push(line);
+    r.extend(&lines[at..]);
+    Ok(r.join("\n") + "\n")
+}
+
+/// Adds `mod dayNN;` to main.rs.
+pub fn register_module(main_rs: &str, day: i32) -> Result<String, String> {
+    insert_sorted(main_rs, "mod day", &format!("mod day{:02};", day))
+}
+
+/// Adds the day to [`crate::solution::registry`].
+pub fn register_runner(solution_rs: &str, day: i32) -> Result<String, String> {
+    let line = format!("        &crate::day{:02}::Day{:02},", day, day);
+    insert_sorted(solution_rs, "        &crate::day", &line)
+}
+
+pub fn input_url(base_url: &str, year: i32, day: i32) -> String {
+    format!(
+        "{}/{}/day/{}/input",
+        base_url.trim_end_matches('/'),
+        year,
+        day
+    )
+}
+
+/// Downloads `url` to `dest` with curl, the same way that prep.sh always has.
+fn fetch(url: &str, cookies: &Path, dest: &Path) -> Result<(), String> {
+    if !cookies.is_file() {
+        return Err(format!(
+            "Session cookie file {} not found; copy the session cookie from the browser into it",
+            cookies.display()
+        ));
+    }
+
+    // download next to the destination first, so a failure doesn't leave a partial input behind
+    let partial = dest.with_extension("txt.part");
+    let status = Command::new("curl")
+        .args(["--fail", "--silent", "--show-error", "--cookie"])
+        .arg(cookies)
+        .arg("--output")
+        .arg(&partial)
+        .arg(url)
+        .status()
+        .map_err(|err| format!("Could not run curl: {}", err))?;
+    if !status.success() {
+        let _ = fs::remove_file(&partial);
+        return Err(format!("Could not download {} ({})", url, status));
+    }
+    fs::rename(&partial, dest).map_err(|err| format!("Could not save {}: {}", dest.display(), err))
+}
+
+fn read(path: &Path) -> Result<String, String> {
+    fs::read_to_string(path).map_err(|err| format!("Could not read {}: {}", path.display(), err))
+}
+
+fn write(path: &Path, contents: &str) -> Result<(), String> {
+    fs::write(path, contents).map_err(|err| format!("Could not write {}: {}", path.display(), err))
+}
+
+/// Fetches the input (unless it's already been downloaded), writes the new module and registers
+/// it. Returns a description of each step taken.
+pub fn scaffold(options: &Scaffold) -> Result<Vec<String>, String> {
+    let src = options.root.join("src");
+    let module = src.join(format!("day{:02}.rs", options.day));
+    if module.exists() {
+        return Err(format!("{} already exists", module.display()));
+    }
+
+    // check these before touching anything
+    let main_path = src.join("main.rs");
+    let solution_path = src.join("solution.rs");
+    let main_rs = register_module(&read(&main_path)?, options.day)
+        .map_err(|err| format!("{}: {}", main_path.display(), err))?;
+    let solution_rs = register_runner(&read(&solution_path)?, options.day)
+        .map_err(|err| format!("{}: {}", solution_path.display(), err))?;
+
+    let mut log = vec![];
+    let input = src.join(format!("day{:02}.txt", options.day));
+    if input.exists() {
+        log.push(format!("Using cached {}", input.display()));
+    } else if options.fetch {
+        let url = input_url(&options.base_url, options.year, options.day);
+        fetch(&url, &options.root.join(&options.cookies), &input)?;
+        log.push(format!("Downloaded {} to {}", url, input.display()));
+    }
+
+    write(&module, &template(options.day, &options.title))?;
+    log.push(format!("Created {}", module.display()));
+
+    let examples = options.root.join("examples");
+    let example = examples.join(format!("day{:02}-1.txt", options.day));
+    if !example.exists() {
+        fs::create_dir_all(&examples)
+            .map_err(|err| format!("Could not create {}: {}", examples.display(), err))?;
+        write(&example, "")?;
+        write(&example.with_extension("toml"), EXAMPLE_ANSWERS)?;
+        log.push(format!("Created {}, for the example", example.display()));
+    }
+    write(&main_path, &main_rs)?;
+    write(&solution_path, &solution_rs)?;
+    log.push(format!("Registered day {}", options.day));
+    Ok(log)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::{Read, Write};
+    use std::net::TcpListener;
+    use std::thread;
+
+    fn main_rs() -> String {
+        [
+            "mod cli;",
+            "mod day01;",
+            "mod day03;",
+            "mod error;",
+            "",
+            "fn main() {}",
+            "",
+        ]
+        .join("\n")
+    }
+
+    fn solution_rs() -> String {
+        [
+            "pub fn registry() -> Vec<&'static dyn Runner> {",
+            "    vec![",
+            "        &crate::day01::Day01,",
+            "        &crate::day03::Day03,",
+            "    ]",
+            "}",
+            "",
+        ]
+        .join("\n")
+    }
+
+    /// A scratch project with main.rs and solution.rs, removed when dropped.
+    struct Project(PathBuf);
+
+    impl Project {
+        fn new(name: &str) -> Project {
+            let root =
+                std::env::temp_dir().join(format!("aoc-scaffold-{}-{}", name, std::process::id()));
+            let _ = fs::remove_dir_all(&root);
+            fs::create_dir_all(root.join("src")).unwrap();
+            fs::write(root.join("src/main.rs"), main_rs()).unwrap();
+            fs::write(root.join("src/solution.rs"), solution_rs()).unwrap();
+            fs::write(root.join(".cookies.txt"), "").unwrap();
+            Project(root)
+        }
+
+        fn options(&self, day: i32, base_url: &str) -> Scaffold {
+            Scaffold {
+                day,
+                year: 2021,
+                title: "Test".to_string(),
+                base_url: base_url.to_string(),
+                cookies: ".cookies.txt".to_string(),
+                root: self.0.clone(),
+                fetch: true,
+            }
+        }
+
+        fn read(&self, path: &str) -> String {
+            fs::read_to_string(self.0.join(path)).unwrap()
+        }
+    }
+
+    impl Drop for Project {
+        fn drop(&mut self) {
+            let _ = fs::remove_dir_all(&self.0);
+        }
+    }
+
+    /// Serves one HTTP response, returning the base URL and a handle yielding the request line.
+    fn mock_server(
+        status: &'static str,
+        body: &'static str,
+    ) -> (String, thread::JoinHandle<String>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        let handle = thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            let mut request = vec![0; 4096];
+            let n = stream.read(&mut request).unwrap();
+            let request = String::from_utf8_lossy(&request[..n]).to_string();
+            write!(
+                stream,
+                "HTTP/1.1 {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                status,
+                body.len(),
+                body
+            )
+            .unwrap();
+            request.lines().next().unwrap_or_default().to_string()
+        });
+        (url, handle)
+    }
+
+    #[test]
+    fn test_register_module() {
+        let actual = register_module(&main_rs(), 2).unwrap();
+        assert!(actual.starts_with("mod cli;\nmod day01;\nmod day02;\nmod day03;\nmod error;\n"));
+        let actual = register_module(&main_rs(), 4).unwrap();
+        assert!(actual.starts_with("mod cli;\nmod day01;\nmod day03;\nmod day04;\nmod error;\n"));
+        assert!(register_module(&main_rs(), 3).is_err());
+    }
+
+    #[test]
+    fn test_register_runner() {
+        let actual = register_runner(&solution_rs(), 2).unwrap();
+        assert!(actual.contains(
+            "        &crate::day01::Day01,\n        &crate::day02::Day02,\n        &crate::day03::Day03,\n"
+        ));
+    }
+
+    #[test]
+    fn test_template() {
+        let actual = template(7, "The \"Treachery\" of Whales");
+        assert!(actual.contains("pub struct Day07;"));
+        assert!(actual.contains("const DAY: i32 = 7;"));
+        assert!(actual.contains("const TITLE: &'static str = \"The \\\"Treachery\\\" of Whales\";"));
+        assert!(actual.contains("util::read_input(7)"));
+    }
+
+    #[test]
+    fn test_input_url() {
+        assert_eq!(
+            input_url("https://adventofcode.com/", 2021, 7),
+            "https://adventofcode.com/2021/day/7/input"
+        );
+    }
+
+    #[test]
+    fn test_scaffold() {
+        let project = Project::new("fetch");
+        let (url, server) = mock_server("200 OK", "1\n2\n3\n");
+
+        let log = scaffold(&project.options(2, &url)).unwrap();
+        assert_eq!(server.join().unwrap(), "GET /2021/day/2/input HTTP/1.1");
+        assert_eq!(log.len(), 4);
+        assert!(project.0.join("examples/day02-1.txt").is_file());
+        assert!(project
+            .read("examples/day02-1.toml")
+            .contains("# part1 = 0"));
+        assert_eq!(project.read("src/day02.txt"), "1\n2\n3\n");
+        assert!(project.read("src/day02.rs").contains("pub struct Day02;"));
+        assert!(project.read("src/main.rs").contains("mod day02;"));
+        assert!(project
+            .read("src/solution.rs")
+            .contains("&crate::day02::Day02,"));
+
+        // the module is there now, so this should refuse without fetching anything
+        let actual = scaffold(&project.options(2, "http://127.0.0.1:1"));
+        assert!(actual.unwrap_err().ends_with("day02.rs already exists"));
+    }
+
+    #[test]
+    fn test_scaffold_cached() {
+        let project = Project::new("cached");
+        fs::write(project.0.join("src/day04.txt"), "cached").unwrap();
+
+        // nothing is listening here, so this would fail if it tried to download
+        let log = scaffold(&project.options(4, "http://127.0.0.1:1")).unwrap();
+        assert!(log[0].starts_with("Using cached"));
+        assert_eq!(project.read("src/day04.txt"), "cached");
+    }
+
+    #[test]
+    fn test_scaffold_download_failed() {
+        let project = Project::new("failed");
+        let (url, server) = mock_server("404 Not Found", "no");
+
+        let actual = scaffold(&project.options(5, &url));
+        server.join().unwrap();
+        assert!(actual.unwrap_err().starts_with("Could not download"));
+        assert!(!project.0.join("src/day05.txt").exists());
+        assert!(!project.0.join("src/day05.rs").exists());
+        assert_eq!(project.read("src/main.rs"), main_rs());
+    }
+}
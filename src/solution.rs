@@ -0,0 +1,238 @@
+use crate::cli::Part;
+use crate::error::ParseError;
+use std::fmt::Display;
+use std::time::Instant;
+
+/// A single day's puzzle: how to parse the input and solve both parts.
+pub trait Solution {
+    type Input;
+    type Output1: Display;
+    type Output2: Display;
+
+    const DAY: i32;
+    const TITLE: &'static str;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError>;
+    fn part1(input: &Self::Input) -> Self::Output1;
+    fn part2(input: &Self::Input) -> Self::Output2;
+
+    /// A closer look at how the answers come about, for `--explain`. `what` names one of the
+    /// views the day offers.
+    fn explain(_input: &Self::Input, _what: &str) -> Result<String, String> {
+        Err(format!("Day {} has nothing to explain", Self::DAY))
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Answer {
+    pub value: String,
+    pub time_ms: f64,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Report {
+    pub day: i32,
+    pub parse_ms: f64,
+    pub part1: Option<Answer>,
+    pub part2: Option<Answer>,
+    pub total_ms: f64,
+    /// CPU time spent by the thread that ran the day, where the platform can tell.
+    pub cpu_ms: Option<f64>,
+}
+
+fn elapsed_ms(start: Instant) -> f64 {
+    start.elapsed().as_secs_f64() * 1000.
+}
+
+/// CPU time used so far by the calling thread. Unlike the wall clock, this doesn't count time
+/// spent waiting for a core while other days run.
+#[cfg(target_os = "linux")]
+fn thread_cpu_ms() -> Option<f64> {
+    use std::os::raw::{c_int, c_long};
+
+    #[repr(C)]
+    struct Timespec {
+        tv_sec: c_long,
+        tv_nsec: c_long,
+    }
+
+    const CLOCK_THREAD_CPUTIME_ID: c_int = 3;
+    extern "C" {
+        fn clock_gettime(clock: c_int, time: *mut Timespec) -> c_int;
+    }
+
+    let mut time = Timespec {
+        tv_sec: 0,
+        tv_nsec: 0,
+    };
+    // SAFETY: `time` is a valid timespec for the call to fill in
+    let status = unsafe { clock_gettime(CLOCK_THREAD_CPUTIME_ID, &mut time) };
+    (status == 0).then(|| time.tv_sec as f64 * 1000. + time.tv_nsec as f64 / 1_000_000.)
+}
+
+#[cfg(not(target_os = "linux"))]
+fn thread_cpu_ms() -> Option<f64> {
+    None
+}
+
+/// Type erased view of a [`Solution`], so that the days can live in one registry.
+pub trait Runner: Sync {
+    fn day(&self) -> i32;
+    fn title(&self) -> &'static str;
+    fn run(&self, input: &str, part: Part) -> Result<Report, ParseError>;
+    fn explain(&self, input: &str, what: &str) -> Result<String, String>;
+}
+
+impl<S: Solution + Sync> Runner for S {
+    fn day(&self) -> i32 {
+        S::DAY
+    }
+
+    fn title(&self) -> &'static str {
+        S::TITLE
+    }
+
+    fn run(&self, input: &str, part: Part) -> Result<Report, ParseError> {
+        let cpu0 = thread_cpu_ms();
+        let n0 = Instant::now();
+        let input = S::parse(input)?;
+        let parse_ms = elapsed_ms(n0);
+
+        let part1 = part.includes_part1().then(|| {
+            let n1 = Instant::now();
+            let value = S::part1(&input).to_string();
+            Answer {
+                value,
+                time_ms: elapsed_ms(n1),
+            }
+        });
+
+        let part2 = part.includes_part2().then(|| {
+            let n2 = Instant::now();
+            let value = S::part2(&input).to_string();
+            Answer {
+                value,
+                time_ms: elapsed_ms(n2),
+            }
+        });
+
+        Ok(Report {
+            day: S::DAY,
+            parse_ms,
+            part1,
+            part2,
+            total_ms: elapsed_ms(n0),
+            cpu_ms: cpu0.zip(thread_cpu_ms()).map(|(start, end)| end - start),
+        })
+    }
+
+    fn explain(&self, input: &str, what: &str) -> Result<String, String> {
+        let input = S::parse(input).map_err(|err| format!("Could not parse input: {}", err))?;
+        S::explain(&input, what)
+    }
+}
+
+/// All of the implemented days, in order.
+pub fn registry() -> Vec<&'static dyn Runner> {
+    vec![
+        &crate::day01::Day01,
+        &crate::day02::Day02,
+        &crate::day03::Day03,
+        &crate::day04::Day04,
+        &crate::day05::Day05,
+        &crate::day06::Day06,
+        &crate::day07::Day07,
+        &crate::day08::Day08,
+        &crate::day09::Day09,
+        &crate::day10::Day10,
+        &crate::day11::Day11,
+        &crate::day12::Day12,
+        &crate::day13::Day13,
+        &crate::day14::Day14,
+        &crate::day15::Day15,
+        &crate::day16::Day16,
+        &crate::day17::Day17,
+        &crate::day18::Day18,
+        &crate::day19::Day19,
+        &crate::day20::Day20,
+        &crate::day21::Day21,
+        &crate::day22::Day22,
+        &crate::day23::Day23,
+        &crate::day24::Day24,
+        &crate::day25::Day25,
+    ]
+}
+
+pub fn find(day: i32) -> Option<&'static dyn Runner> {
+    registry().into_iter().find(|runner| runner.day() == day)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::util;
+
+    #[test]
+    fn test_registry_in_order() {
+        let days: Vec<i32> = registry().iter().map(|runner| runner.day()).collect();
+        assert_eq!(days, (1..=crate::cli::NUM_DAYS).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn test_registry_titles() {
+        for runner in registry() {
+            assert!(
+                !runner.title().is_empty(),
+                "Day {} has no title",
+                runner.day()
+            );
+        }
+    }
+
+    #[test]
+    fn test_find() {
+        assert_eq!(find(15).map(|runner| runner.title()), Some("Chiton"));
+        assert!(find(26).is_none());
+    }
+
+    #[test]
+    fn test_run() {
+        let report = find(1)
+            .unwrap()
+            .run(&util::read_input(1), Part::Both)
+            .unwrap();
+        assert_eq!(report.day, 1);
+        assert_eq!(report.part1.map(|a| a.value), Some("1688".to_string()));
+        assert_eq!(report.part2.map(|a| a.value), Some("1728".to_string()));
+    }
+
+    #[test]
+    fn test_thread_cpu_ms() {
+        let start = thread_cpu_ms();
+        if cfg!(target_os = "linux") {
+            assert!(start.is_some());
+        }
+        // sleeping takes no CPU time, while spinning does
+        let spin = Instant::now();
+        while spin.elapsed().as_millis() < 20 {}
+        if let (Some(start), Some(end)) = (start, thread_cpu_ms()) {
+            assert!(end - start >= 10., "{}", end - start);
+        }
+    }
+
+    #[test]
+    fn test_run_invalid_input() {
+        let err = find(1).unwrap().run("199\n2x0\n", Part::Both).unwrap_err();
+        assert_eq!((err.day, err.line, err.column), (1, 2, 1));
+    }
+
+    #[test]
+    fn test_run_one_part() {
+        let report = find(1)
+            .unwrap()
+            .run(&util::read_input(1), Part::Two)
+            .unwrap();
+        assert_eq!(report.part1, None);
+        assert_eq!(report.part2.map(|a| a.value), Some("1728".to_string()));
+    }
+}
@@ -1,34 +1,69 @@
-use std::fs;
+mod grid;
+mod neighborhood;
+pub mod search;
 
+pub use grid::Grid;
+pub use neighborhood::{neighborhood, Edges, Neighborhood};
+
+use crate::error::{input_lines, ParseError};
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::{env, fs, io};
+
+pub const DEFAULT_INPUT_DIR: &str = "./src";
+
+/// Environment variable overriding [`DEFAULT_INPUT_DIR`].
+pub const INPUT_DIR_VAR: &str = "AOC_INPUT_DIR";
+
+pub fn input_dir() -> String {
+    env::var(INPUT_DIR_VAR).unwrap_or_else(|_| DEFAULT_INPUT_DIR.to_string())
+}
+
+pub fn input_path(dir: &str, day: i32) -> PathBuf {
+    Path::new(dir).join(format!("day{:02}.txt", day))
+}
+
+#[cfg(test)]
 pub fn read_input(day: i32) -> String {
-    let filename = format!("./src/day{:02}.txt", day);
-    return fs::read_to_string(filename).expect("Something went wrong reading the file");
+    read_input_file(input_path(&input_dir(), day)).unwrap_or_else(|err| panic!("{}", err))
 }
 
-pub fn as_ints(input: &str) -> Vec<i32> {
-    return input
-        .trim()
-        .split(|c| c == ',' || char::is_whitespace(c))
-        .map(|s| s.parse().expect("Could not parse number"))
-        .collect();
+#[cfg(test)]
+pub const EXAMPLE_DIR: &str = "./examples";
+
+/// The `n`th example from the puzzle description, from `examples/dayNN-n.txt`, without its final
+/// newline.
+#[cfg(test)]
+pub fn read_example(day: i32, n: usize) -> String {
+    let path = Path::new(EXAMPLE_DIR).join(format!("day{:02}-{}.txt", day, n));
+    let example = read_input_file(path).unwrap_or_else(|err| panic!("{}", err));
+    example.trim_end_matches('\n').to_string()
 }
 
-pub fn neighbors(x: usize, y: usize, max_x: usize, max_y: usize) -> Vec<(usize, usize)> {
+/// Reads a puzzle input, or stdin if the path is `-`.
+pub fn read_input_file<P: AsRef<Path>>(path: P) -> Result<String, String> {
+    let path = path.as_ref();
+    let r = if path == Path::new("-") {
+        let mut input = String::new();
+        io::stdin().read_to_string(&mut input).map(|_| input)
+    } else {
+        fs::read_to_string(path)
+    };
+    r.map_err(|err| format!("Could not read input from {}: {}", path.display(), err))
+}
+
+pub fn as_ints(day: i32, input: &str) -> Result<Vec<i32>, ParseError> {
     let mut r = vec![];
-    let min_x = if x == 0 { 0 } else { x - 1 };
-    let min_y = if y == 0 { 0 } else { y - 1 };
-    let max_x = max_x.min(x + 2);
-    let max_y = max_y.min(y + 2);
-
-    for x_n in min_x..max_x {
-        for y_n in min_y..max_y {
-            if !(x_n == x && y_n == y) {
-                r.push((x_n, y_n));
-            }
+    for line in input_lines(day, input) {
+        for s in line
+            .text
+            .split(|c| c == ',' || char::is_whitespace(c))
+            .filter(|s| !s.is_empty())
+        {
+            r.push(line.parse(s, "a number")?);
         }
     }
-
-    r
+    Ok(r)
 }
 
 #[cfg(test)]
@@ -36,38 +71,30 @@ mod tests {
     use super::*;
 
     #[test]
-    fn test_neighbors_top_left() {
-        let actual = neighbors(0, 0, 3, 3);
-        assert_eq!(actual, vec![(0, 1), (1, 0), (1, 1)]);
+    fn test_input_path() {
+        let actual = input_path("inputs/alice", 7);
+        assert_eq!(actual, PathBuf::from("inputs/alice/day07.txt"));
     }
 
     #[test]
-    fn test_neighbors_bottom_right() {
-        let actual = neighbors(2, 2, 3, 3);
-        assert_eq!(actual, vec![(1, 1), (1, 2), (2, 1)]);
+    fn test_read_input_file_missing() {
+        let actual = read_input_file("no/such/day01.txt").unwrap_err();
+        assert!(
+            actual.starts_with("Could not read input from no/such/day01.txt: "),
+            "{}",
+            actual
+        );
     }
 
     #[test]
-    fn test_neighbors_middle() {
-        let actual = neighbors(1, 1, 3, 3);
-        assert_eq!(
-            actual,
-            vec![
-                (0, 0),
-                (0, 1),
-                (0, 2),
-                (1, 0),
-                (1, 2),
-                (2, 0),
-                (2, 1),
-                (2, 2),
-            ]
-        );
+    fn test_as_ints() {
+        let actual = as_ints(1, "1,2,3,4,5\n6,7,8\n");
+        assert_eq!(actual, Ok(vec![1, 2, 3, 4, 5, 6, 7, 8]));
     }
 
     #[test]
-    fn test_as_ints() {
-        let actual = as_ints("1,2,3,4,5\n6,7,8\n");
-        assert_eq!(actual, vec![1, 2, 3, 4, 5, 6, 7, 8]);
+    fn test_as_ints_invalid() {
+        let actual = as_ints(6, "1,2,3\n4,five,6\n");
+        assert_eq!(actual, Err(ParseError::new(6, 2, 3, "a number")));
     }
 }
@@ -0,0 +1,308 @@
+use super::neighborhood::{self, Edges, Neighborhood};
+use crate::error::{input_lines, ParseError};
+use std::fmt;
+use std::ops::{Index, IndexMut};
+
+/// A rectangular grid of cells, stored row by row. Cells are addressed as `(x, y)`, with `(0, 0)`
+/// in the top left corner.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct Grid<T> {
+    width: usize,
+    height: usize,
+    cells: Vec<T>,
+}
+
+impl<T> Grid<T> {
+    pub fn new(width: usize, height: usize, value: T) -> Grid<T>
+    where
+        T: Clone,
+    {
+        Grid {
+            width,
+            height,
+            cells: vec![value; width * height],
+        }
+    }
+
+    /// Parses a map with one character per cell, such as a height map. `parse_cell` returns
+    /// `None` for characters that aren't allowed, which are reported as `expected`.
+    pub fn parse<F>(
+        day: i32,
+        input: &str,
+        expected: &str,
+        mut parse_cell: F,
+    ) -> Result<Grid<T>, ParseError>
+    where
+        F: FnMut(char) -> Option<T>,
+    {
+        let mut cells = vec![];
+        let mut width = None;
+        let mut height = 0;
+
+        for line in input_lines(day, input) {
+            let mut row_width = 0;
+            for (column, ch) in line.chars() {
+                if Some(row_width) == width {
+                    return Err(line.error_at(&line.text[column - 1..], "the end of the row"));
+                }
+                let cell = parse_cell(ch)
+                    .ok_or_else(|| ParseError::new(day, line.number, column, expected))?;
+                cells.push(cell);
+                row_width += 1;
+            }
+            match width {
+                Some(width) if row_width < width => {
+                    return Err(line.error_at_end(&format!("a row of {} cells", width)));
+                }
+                _ => width = Some(row_width),
+            }
+            height += 1;
+        }
+
+        Ok(Grid {
+            width: width.unwrap_or(0),
+            height,
+            cells,
+        })
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn size(&self) -> (usize, usize) {
+        (self.width, self.height)
+    }
+
+    /// The positions of the neighbors of `pos`. The iterator doesn't borrow the grid, so cells can
+    /// be updated while walking it.
+    pub fn neighbors(
+        &self,
+        pos: (usize, usize),
+        kind: Neighborhood,
+        edges: Edges,
+    ) -> impl Iterator<Item = (usize, usize)> {
+        neighborhood::neighborhood(pos, self.size(), kind, edges)
+    }
+
+    /// The position `delta` away from `pos`, or `None` if it's off the grid.
+    pub fn step(
+        &self,
+        pos: (usize, usize),
+        delta: (isize, isize),
+        edges: Edges,
+    ) -> Option<(usize, usize)> {
+        neighborhood::step(pos, delta, self.size(), edges)
+    }
+
+    fn offset(&self, x: usize, y: usize) -> Option<usize> {
+        (x < self.width && y < self.height).then(|| y * self.width + x)
+    }
+
+    pub fn get(&self, x: usize, y: usize) -> Option<&T> {
+        self.offset(x, y).map(|i| &self.cells[i])
+    }
+
+    pub fn get_mut(&mut self, x: usize, y: usize) -> Option<&mut T> {
+        self.offset(x, y).map(move |i| &mut self.cells[i])
+    }
+
+    /// Replaces a cell, returning the old value, or `None` if `(x, y)` is outside of the grid.
+    pub fn set(&mut self, x: usize, y: usize, value: T) -> Option<T> {
+        self.get_mut(x, y)
+            .map(|cell| std::mem::replace(cell, value))
+    }
+
+    /// Every cell along with its position, row by row.
+    pub fn cells(&self) -> impl Iterator<Item = ((usize, usize), &T)> {
+        let width = self.width;
+        self.cells
+            .iter()
+            .enumerate()
+            .map(move |(i, cell)| ((i % width, i / width), cell))
+    }
+
+    /// Every cell, row by row.
+    pub fn iter(&self) -> std::slice::Iter<'_, T> {
+        self.cells.iter()
+    }
+
+    pub fn iter_mut(&mut self) -> std::slice::IterMut<'_, T> {
+        self.cells.iter_mut()
+    }
+
+    pub fn rows(&self) -> impl Iterator<Item = &[T]> {
+        // chunks panics on 0, and an empty grid has no rows anyway
+        self.cells.chunks(self.width.max(1))
+    }
+
+    /// A grid of the same size, with `f` applied to each cell.
+    pub fn map<U, F: FnMut(&T) -> U>(&self, f: F) -> Grid<U> {
+        Grid {
+            width: self.width,
+            height: self.height,
+            cells: self.cells.iter().map(f).collect(),
+        }
+    }
+}
+
+impl<T> Index<(usize, usize)> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, (x, y): (usize, usize)) -> &T {
+        self.get(x, y).unwrap_or_else(|| {
+            panic!(
+                "({}, {}) is outside of the {}x{} grid",
+                x, y, self.width, self.height
+            )
+        })
+    }
+}
+
+impl<T> IndexMut<(usize, usize)> for Grid<T> {
+    fn index_mut(&mut self, (x, y): (usize, usize)) -> &mut T {
+        match self.offset(x, y) {
+            Some(i) => &mut self.cells[i],
+            None => panic!(
+                "({}, {}) is outside of the {}x{} grid",
+                x, y, self.width, self.height
+            ),
+        }
+    }
+}
+
+/// Builds a grid from its rows, which must all be the same length.
+impl<T> FromIterator<Vec<T>> for Grid<T> {
+    fn from_iter<I: IntoIterator<Item = Vec<T>>>(rows: I) -> Grid<T> {
+        let mut cells = vec![];
+        let mut width = None;
+        let mut height = 0;
+        for row in rows {
+            match width {
+                Some(width) => assert_eq!(row.len(), width, "row {} has the wrong length", height),
+                None => width = Some(row.len()),
+            }
+            cells.extend(row);
+            height += 1;
+        }
+        Grid {
+            width: width.unwrap_or(0),
+            height,
+            cells,
+        }
+    }
+}
+
+impl<T> From<Vec<Vec<T>>> for Grid<T> {
+    fn from(rows: Vec<Vec<T>>) -> Grid<T> {
+        rows.into_iter().collect()
+    }
+}
+
+impl<T, const W: usize, const H: usize> From<[[T; W]; H]> for Grid<T> {
+    fn from(rows: [[T; W]; H]) -> Grid<T> {
+        Grid {
+            width: W,
+            height: H,
+            cells: rows.into_iter().flatten().collect(),
+        }
+    }
+}
+
+/// Renders each cell with its own `Display`, one line per row.
+impl<T: fmt::Display> fmt::Display for Grid<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for (y, row) in self.rows().enumerate() {
+            if y > 0 {
+                writeln!(f)?;
+            }
+            for cell in row {
+                write!(f, "{}", cell)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn digits(input: &str) -> Result<Grid<u32>, ParseError> {
+        Grid::parse(9, input, "a digit", |ch| ch.to_digit(10))
+    }
+
+    #[test]
+    fn test_parse() {
+        let actual = digits("123\n456").unwrap();
+        assert_eq!((actual.width(), actual.height()), (3, 2));
+        assert_eq!(actual, Grid::from([[1, 2, 3], [4, 5, 6]]));
+        assert_eq!(actual.to_string(), "123\n456");
+    }
+
+    #[test]
+    fn test_parse_invalid() {
+        assert_eq!(digits("123\n4x6"), Err(ParseError::new(9, 2, 2, "a digit")));
+        assert_eq!(
+            digits("123\n45"),
+            Err(ParseError::new(9, 2, 3, "a row of 3 cells"))
+        );
+        assert_eq!(
+            digits("123\n4567"),
+            Err(ParseError::new(9, 2, 4, "the end of the row"))
+        );
+    }
+
+    #[test]
+    fn test_parse_empty() {
+        let actual = digits("").unwrap();
+        assert_eq!((actual.width(), actual.height()), (0, 0));
+        assert_eq!(actual.rows().count(), 0);
+    }
+
+    #[test]
+    fn test_get_set() {
+        let mut grid = Grid::new(3, 2, '.');
+        assert_eq!(grid.set(2, 1, '#'), Some('.'));
+        assert_eq!(grid.set(3, 1, '#'), None);
+        assert_eq!(grid.get(2, 1), Some(&'#'));
+        assert_eq!(grid.get(1, 2), None);
+        grid[(0, 0)] = '@';
+        assert_eq!(grid.to_string(), "@..\n..#");
+    }
+
+    #[test]
+    #[should_panic(expected = "(3, 0) is outside of the 3x2 grid")]
+    fn test_index_out_of_bounds() {
+        let grid = Grid::new(3, 2, 0);
+        let _ = grid[(3, 0)];
+    }
+
+    #[test]
+    fn test_iterators() {
+        let grid = Grid::from(vec![vec![1, 2], vec![3, 4]]);
+        let cells: Vec<_> = grid.cells().map(|(p, &v)| (p, v)).collect();
+        assert_eq!(
+            cells,
+            vec![((0, 0), 1), ((1, 0), 2), ((0, 1), 3), ((1, 1), 4)]
+        );
+        let rows: Vec<&[i32]> = grid.rows().collect();
+        assert_eq!(rows, vec![&[1, 2], &[3, 4]]);
+        assert_eq!(grid.map(|v| v * 10), Grid::from([[10, 20], [30, 40]]));
+    }
+
+    #[test]
+    fn test_neighbors() {
+        let mut grid = Grid::new(3, 3, 0);
+        for pos in grid.neighbors((0, 1), Neighborhood::Orthogonal, Edges::Wrap) {
+            grid[pos] += 1;
+        }
+        assert_eq!(grid.to_string(), "100\n011\n100");
+        assert_eq!(grid.step((0, 1), (-1, 0), Edges::Clip), None);
+        assert_eq!(grid.step((0, 1), (-1, 0), Edges::Wrap), Some((2, 1)));
+    }
+}
@@ -0,0 +1,151 @@
+/// Which cells count as neighbors of a cell.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Neighborhood {
+    /// North, west, east and south.
+    Orthogonal,
+    /// The four corners.
+    #[allow(dead_code)] // no puzzle needs it yet
+    Diagonal,
+    /// All eight surrounding cells.
+    All,
+}
+
+/// What happens to neighbors that fall outside of the grid.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Edges {
+    /// They are left out.
+    Clip,
+    /// They wrap around to the other side, as on a torus.
+    Wrap,
+}
+
+impl Neighborhood {
+    /// The `(dx, dy)` of each neighbor, column by column.
+    pub fn offsets(self) -> &'static [(isize, isize)] {
+        match self {
+            Neighborhood::Orthogonal => &[(-1, 0), (0, -1), (0, 1), (1, 0)],
+            Neighborhood::Diagonal => &[(-1, -1), (-1, 1), (1, -1), (1, 1)],
+            Neighborhood::All => &[
+                (-1, -1),
+                (-1, 0),
+                (-1, 1),
+                (0, -1),
+                (0, 1),
+                (1, -1),
+                (1, 0),
+                (1, 1),
+            ],
+        }
+    }
+}
+
+fn step_axis(pos: usize, delta: isize, len: usize, edges: Edges) -> Option<usize> {
+    match edges {
+        Edges::Clip => pos.checked_add_signed(delta).filter(|&p| p < len),
+        Edges::Wrap => {
+            let p = (pos as isize + delta).rem_euclid(len as isize);
+            Some(p as usize)
+        }
+    }
+}
+
+/// The cell `delta` away from `pos` in a grid of `size`, or `None` if it's off the grid.
+pub fn step(
+    pos: (usize, usize),
+    delta: (isize, isize),
+    size: (usize, usize),
+    edges: Edges,
+) -> Option<(usize, usize)> {
+    if pos.0 >= size.0 || pos.1 >= size.1 {
+        return None;
+    }
+    let x = step_axis(pos.0, delta.0, size.0, edges)?;
+    let y = step_axis(pos.1, delta.1, size.1, edges)?;
+    Some((x, y))
+}
+
+/// The neighbors of `pos` in a grid of `size`. With `Edges::Wrap`, a grid less than three cells
+/// wide or high has neighbors that are the same cell, or `pos` itself.
+pub fn neighborhood(
+    pos: (usize, usize),
+    size: (usize, usize),
+    kind: Neighborhood,
+    edges: Edges,
+) -> impl Iterator<Item = (usize, usize)> {
+    kind.offsets()
+        .iter()
+        .filter_map(move |&delta| step(pos, delta, size, edges))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn collect(pos: (usize, usize), kind: Neighborhood, edges: Edges) -> Vec<(usize, usize)> {
+        neighborhood(pos, (4, 3), kind, edges).collect()
+    }
+
+    #[test]
+    fn test_orthogonal() {
+        let actual = collect((1, 1), Neighborhood::Orthogonal, Edges::Clip);
+        assert_eq!(actual, vec![(0, 1), (1, 0), (1, 2), (2, 1)]);
+        let actual = collect((0, 0), Neighborhood::Orthogonal, Edges::Clip);
+        assert_eq!(actual, vec![(0, 1), (1, 0)]);
+    }
+
+    #[test]
+    fn test_diagonal() {
+        let actual = collect((3, 2), Neighborhood::Diagonal, Edges::Clip);
+        assert_eq!(actual, vec![(2, 1)]);
+        let actual = collect((3, 2), Neighborhood::Diagonal, Edges::Wrap);
+        assert_eq!(actual, vec![(2, 1), (2, 0), (0, 1), (0, 0)]);
+    }
+
+    #[test]
+    fn test_all_clip() {
+        let actual = collect((0, 0), Neighborhood::All, Edges::Clip);
+        assert_eq!(actual, vec![(0, 1), (1, 0), (1, 1)]);
+        let actual = collect((3, 2), Neighborhood::All, Edges::Clip);
+        assert_eq!(actual, vec![(2, 1), (2, 2), (3, 1)]);
+        let actual = collect((1, 1), Neighborhood::All, Edges::Clip);
+        assert_eq!(
+            actual,
+            vec![
+                (0, 0),
+                (0, 1),
+                (0, 2),
+                (1, 0),
+                (1, 2),
+                (2, 0),
+                (2, 1),
+                (2, 2),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_all_wrap() {
+        let actual = collect((0, 0), Neighborhood::All, Edges::Wrap);
+        assert_eq!(
+            actual,
+            vec![
+                (3, 2),
+                (3, 0),
+                (3, 1),
+                (0, 2),
+                (0, 1),
+                (1, 2),
+                (1, 0),
+                (1, 1),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_step() {
+        assert_eq!(step((0, 1), (-1, 0), (4, 3), Edges::Clip), None);
+        assert_eq!(step((0, 1), (-1, 0), (4, 3), Edges::Wrap), Some((3, 1)));
+        assert_eq!(step((2, 2), (0, 1), (4, 3), Edges::Wrap), Some((2, 0)));
+        assert_eq!(step((4, 0), (-1, 0), (4, 3), Edges::Clip), None);
+    }
+}
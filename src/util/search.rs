@@ -0,0 +1,350 @@
+use std::cmp::Reverse;
+use std::collections::hash_map::Entry;
+use std::collections::{BinaryHeap, HashMap, VecDeque};
+use std::hash::Hash;
+use std::ops::Add;
+
+/// Anything that can be used as the cost of a step. `Default` is the cost of the empty path.
+pub trait Cost: Copy + Ord + Add<Output = Self> + Default {}
+
+impl<C: Copy + Ord + Add<Output = C> + Default> Cost for C {}
+
+/// The states from the start to a goal, both included, and the total cost of the steps between
+/// them.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Path<S, C> {
+    pub states: Vec<S>,
+    pub cost: C,
+}
+
+/// Every state seen so far, with the cheapest known way to reach it. States are numbered in the
+/// order they were first seen, so that the heap and the parent links don't need to clone them.
+struct Visited<S, C> {
+    ids: HashMap<S, usize>,
+    states: Vec<S>,
+    parents: Vec<Option<usize>>,
+    costs: Vec<C>,
+}
+
+impl<S: Clone + Eq + Hash, C: Cost> Visited<S, C> {
+    fn new(start: S) -> Visited<S, C> {
+        Visited {
+            ids: HashMap::from([(start.clone(), 0)]),
+            states: vec![start],
+            parents: vec![None],
+            costs: vec![C::default()],
+        }
+    }
+
+    /// Records that `state` can be reached from `parent` for `cost`. Returns its id if that's
+    /// cheaper than any way seen before.
+    fn relax(&mut self, state: S, parent: usize, cost: C) -> Option<usize> {
+        match self.ids.entry(state) {
+            Entry::Occupied(entry) => {
+                let id = *entry.get();
+                if cost >= self.costs[id] {
+                    return None;
+                }
+                self.parents[id] = Some(parent);
+                self.costs[id] = cost;
+                Some(id)
+            }
+            Entry::Vacant(entry) => {
+                let id = self.states.len();
+                self.states.push(entry.key().clone());
+                entry.insert(id);
+                self.parents.push(Some(parent));
+                self.costs.push(cost);
+                Some(id)
+            }
+        }
+    }
+
+    fn path(&self, mut id: usize) -> Path<S, C> {
+        let cost = self.costs[id];
+        let mut states = vec![self.states[id].clone()];
+        while let Some(parent) = self.parents[id] {
+            states.push(self.states[parent].clone());
+            id = parent;
+        }
+        states.reverse();
+        Path { states, cost }
+    }
+}
+
+/// Breadth-first search, for when every step costs the same. The cost is the number of steps.
+#[allow(dead_code)] // kept alongside the weighted searches until a puzzle needs it
+pub fn bfs<S, I, F, G>(start: S, successors: F, is_goal: G) -> Option<Path<S, usize>>
+where
+    S: Clone + Eq + Hash,
+    I: IntoIterator<Item = S>,
+    F: FnMut(&S) -> I,
+    G: FnMut(&S) -> bool,
+{
+    let (visited, goal) = breadth_first(start, successors, is_goal);
+    goal.map(|id| visited.path(id))
+}
+
+/// Dijkstra's algorithm: the cheapest path from `start` to any state for which `is_goal` is true.
+/// `successors` returns the states one step away, each with the cost of that step.
+pub fn dijkstra<S, C, I, F, G>(start: S, successors: F, is_goal: G) -> Option<Path<S, C>>
+where
+    S: Clone + Eq + Hash,
+    C: Cost,
+    I: IntoIterator<Item = (S, C)>,
+    F: FnMut(&S) -> I,
+    G: FnMut(&S) -> bool,
+{
+    astar(start, successors, |_| C::default(), is_goal)
+}
+
+/// A* search. `heuristic` estimates the remaining cost to a goal, and must never overestimate it
+/// for the path to be the cheapest one.
+pub fn astar<S, C, I, F, H, G>(
+    start: S,
+    successors: F,
+    heuristic: H,
+    is_goal: G,
+) -> Option<Path<S, C>>
+where
+    S: Clone + Eq + Hash,
+    C: Cost,
+    I: IntoIterator<Item = (S, C)>,
+    F: FnMut(&S) -> I,
+    H: FnMut(&S) -> C,
+    G: FnMut(&S) -> bool,
+{
+    let (visited, goal) = search(start, successors, heuristic, is_goal);
+    goal.map(|id| visited.path(id))
+}
+
+/// The cheapest paths from one start to every state that can be reached from it.
+pub struct Reachable<S, C> {
+    visited: Visited<S, C>,
+}
+
+impl<S: Clone + Eq + Hash, C: Cost> Reachable<S, C> {
+    pub fn cost(&self, state: &S) -> Option<C> {
+        self.visited
+            .ids
+            .get(state)
+            .map(|&id| self.visited.costs[id])
+    }
+
+    #[allow(dead_code)] // no puzzle asks for the routes to more than one goal yet
+    pub fn path(&self, state: &S) -> Option<Path<S, C>> {
+        self.visited.ids.get(state).map(|&id| self.visited.path(id))
+    }
+
+    /// The number of reachable states, including the start.
+    pub fn len(&self) -> usize {
+        self.visited.states.len()
+    }
+}
+
+/// Breadth-first search without a goal, for when every step costs the same. The cost of each
+/// reachable state is its number of steps from the start.
+pub fn bfs_all<S, I, F>(start: S, successors: F) -> Reachable<S, usize>
+where
+    S: Clone + Eq + Hash,
+    I: IntoIterator<Item = S>,
+    F: FnMut(&S) -> I,
+{
+    let (visited, _) = breadth_first(start, successors, |_| false);
+    Reachable { visited }
+}
+
+/// The breadth-first loop shared by the searches above. Returns the id of the goal, if one was
+/// reached.
+fn breadth_first<S, I, F, G>(
+    start: S,
+    mut successors: F,
+    mut is_goal: G,
+) -> (Visited<S, usize>, Option<usize>)
+where
+    S: Clone + Eq + Hash,
+    I: IntoIterator<Item = S>,
+    F: FnMut(&S) -> I,
+    G: FnMut(&S) -> bool,
+{
+    let mut visited = Visited::new(start);
+    let mut queue = VecDeque::from([0]);
+
+    while let Some(id) = queue.pop_front() {
+        if is_goal(&visited.states[id]) {
+            return (visited, Some(id));
+        }
+        let cost = visited.costs[id] + 1;
+        for next in successors(&visited.states[id]) {
+            // the first visit is always the shortest, so this only ever adds new states
+            if let Some(next_id) = visited.relax(next, id, cost) {
+                queue.push_back(next_id);
+            }
+        }
+    }
+
+    (visited, None)
+}
+
+/// Dijkstra's algorithm without a goal, run until every reachable state has been found.
+pub fn dijkstra_all<S, C, I, F>(start: S, successors: F) -> Reachable<S, C>
+where
+    S: Clone + Eq + Hash,
+    C: Cost,
+    I: IntoIterator<Item = (S, C)>,
+    F: FnMut(&S) -> I,
+{
+    let (visited, _) = search(start, successors, |_| C::default(), |_| false);
+    Reachable { visited }
+}
+
+/// The A* loop shared by the searches above. Returns the id of the goal, if one was reached.
+fn search<S, C, I, F, H, G>(
+    start: S,
+    mut successors: F,
+    mut heuristic: H,
+    mut is_goal: G,
+) -> (Visited<S, C>, Option<usize>)
+where
+    S: Clone + Eq + Hash,
+    C: Cost,
+    I: IntoIterator<Item = (S, C)>,
+    F: FnMut(&S) -> I,
+    H: FnMut(&S) -> C,
+    G: FnMut(&S) -> bool,
+{
+    let mut visited = Visited::new(start);
+    let start_estimate = heuristic(&visited.states[0]);
+    let mut heap = BinaryHeap::from([(Reverse(start_estimate), Reverse(C::default()), 0)]);
+
+    while let Some((_, Reverse(cost), id)) = heap.pop() {
+        // a cheaper way to this state was found after this entry was pushed
+        if cost > visited.costs[id] {
+            continue;
+        }
+        if is_goal(&visited.states[id]) {
+            return (visited, Some(id));
+        }
+        for (next, step) in successors(&visited.states[id]) {
+            let next_cost = cost + step;
+            let estimate = next_cost + heuristic(&next);
+            if let Some(next_id) = visited.relax(next, id, next_cost) {
+                heap.push((Reverse(estimate), Reverse(next_cost), next_id));
+            }
+        }
+    }
+
+    (visited, None)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A small weighted graph, where the direct edge from 'a' to 'd' is the most expensive way.
+    fn edges(node: &char) -> Vec<(char, u32)> {
+        match node {
+            'a' => vec![('b', 1), ('c', 4), ('d', 10)],
+            'b' => vec![('c', 1), ('a', 1)],
+            'c' => vec![('d', 2)],
+            _ => vec![],
+        }
+    }
+
+    #[test]
+    fn test_dijkstra() {
+        let actual = dijkstra('a', edges, |&n| n == 'd');
+        assert_eq!(
+            actual,
+            Some(Path {
+                states: vec!['a', 'b', 'c', 'd'],
+                cost: 4
+            })
+        );
+    }
+
+    #[test]
+    fn test_dijkstra_start_is_goal() {
+        let actual = dijkstra('a', edges, |&n| n == 'a');
+        assert_eq!(
+            actual,
+            Some(Path {
+                states: vec!['a'],
+                cost: 0
+            })
+        );
+    }
+
+    #[test]
+    fn test_dijkstra_unreachable() {
+        assert_eq!(dijkstra('c', edges, |&n| n == 'a'), None);
+    }
+
+    #[test]
+    fn test_dijkstra_all() {
+        let actual = dijkstra_all('a', edges);
+        assert_eq!(actual.len(), 4);
+        assert_eq!(actual.cost(&'c'), Some(2));
+        assert_eq!(actual.cost(&'d'), Some(4));
+        assert_eq!(
+            actual.path(&'c').map(|p| p.states),
+            Some(vec!['a', 'b', 'c'])
+        );
+        assert_eq!(dijkstra_all('c', edges).cost(&'a'), None);
+    }
+
+    #[test]
+    fn test_astar() {
+        // a 5x5 room with a wall down the middle that has a gap at the bottom
+        let open =
+            |&(x, y): &(i32, i32)| (0..5).contains(&x) && (0..5).contains(&y) && (x != 2 || y == 4);
+        let successors = |&(x, y): &(i32, i32)| {
+            [(x - 1, y), (x + 1, y), (x, y - 1), (x, y + 1)]
+                .into_iter()
+                .filter(open)
+                .map(|p| (p, 1))
+        };
+        let manhattan = |&(x, y): &(i32, i32)| (4 - x).abs() + y.abs();
+        let actual = astar((0, 0), successors, manhattan, |&p| p == (4, 0)).unwrap();
+        assert_eq!(actual.cost, 12);
+        assert_eq!(actual.states.len(), 13);
+        assert!(actual.states.iter().all(open));
+        let expected = dijkstra((0, 0), successors, |&p| p == (4, 0)).unwrap();
+        assert_eq!(actual.cost, expected.cost);
+    }
+
+    /// Knight moves on a four by four board.
+    fn knight_moves(&(x, y): &(i32, i32)) -> impl Iterator<Item = (i32, i32)> {
+        [
+            (1, 2),
+            (2, 1),
+            (-1, 2),
+            (-2, 1),
+            (1, -2),
+            (2, -1),
+            (-1, -2),
+            (-2, -1),
+        ]
+        .into_iter()
+        .map(move |(dx, dy)| (x + dx, y + dy))
+        .filter(|&(x, y)| (0..4).contains(&x) && (0..4).contains(&y))
+    }
+
+    #[test]
+    fn test_bfs() {
+        let actual = bfs((0, 0), knight_moves, |&p| p == (3, 3)).unwrap();
+        assert_eq!(actual.cost, 2);
+        assert_eq!(actual.states.len(), 3);
+        assert_eq!(bfs((0, 0), knight_moves, |&p| p == (9, 9)), None);
+    }
+
+    #[test]
+    fn test_bfs_all() {
+        let actual = bfs_all((0, 0), knight_moves);
+        assert_eq!(actual.len(), 16);
+        assert_eq!(actual.cost(&(0, 0)), Some(0));
+        assert_eq!(actual.cost(&(3, 3)), Some(2));
+        assert_eq!(actual.path(&(3, 3)).map(|p| p.states.len()), Some(3));
+        assert_eq!(actual.cost(&(9, 9)), None);
+    }
+}